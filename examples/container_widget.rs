@@ -0,0 +1,106 @@
+use std::rc::Rc;
+
+use gloo::console::log;
+use gloo::timers::future::TimeoutFuture;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use sycatree::components::{LoadResult, NodeState, RowContext, TreeView};
+use sycatree::model::Node;
+use sycatree::persist::{sync_to_store, TreeStore};
+use uuid::Uuid;
+
+#[component]
+async fn ContainerWidget<G: Html>(cx: Scope<'_>) -> View<G> {
+    // UUIDs keep nodes added in different tabs from clashing once their edits meet.
+    let ids: Vec<Uuid> = (0..12).map(|_| Uuid::new_v4()).collect();
+    let vec_nodes: Vec<Node<(), Uuid>> = vec![
+        Node::new(ids[0], None, "Node 1"),
+        Node::new(ids[1], Some(ids[0]), "Node 2"),
+        Node::new(ids[2], Some(ids[1]), "Node 3"),
+        Node::new(ids[3], Some(ids[2]), "Node 4"),
+        Node::new(ids[4], Some(ids[0]), "Node 5"),
+        Node::new(ids[5], None, "Node 6"),
+        Node::new(ids[6], None, "Node 7"),
+        Node::new(ids[7], None, "Node 8").with_lazy_children(),
+        Node::new(ids[8], Some(ids[6]), "Node 9"),
+        Node::new(ids[9], Some(ids[8]), "Node 10"),
+        Node::new(ids[10], Some(ids[8]), "Node 11"),
+        Node::new(ids[11], Some(ids[6]), "node 12"),
+    ];
+
+    let state = match TreeStore::open("sycatree-uuid").await {
+        Ok(store) => {
+            let store = Rc::new(store);
+            // Stored data isn't trusted: a broken tree is reported and left as it is.
+            match store
+                .load_or_seed(vec_nodes.clone())
+                .await
+                .map(NodeState::try_new)
+            {
+                Ok(Ok(state)) => {
+                    let state = create_ref(cx, state);
+                    sync_to_store(cx, store, state);
+                    state
+                }
+                Ok(Err(errors)) => {
+                    for err in errors {
+                        log!(format!("Stored tree is broken, not saving: {}", err));
+                    }
+                    create_ref(cx, NodeState::new(vec_nodes))
+                }
+                Err(err) => {
+                    log!(format!("Failed to load tree: {}", err));
+                    create_ref(cx, NodeState::new(vec_nodes))
+                }
+            }
+        }
+        Err(err) => {
+            log!(format!("Tree won't be saved: {}", err));
+            create_ref(cx, NodeState::new(vec_nodes))
+        }
+    };
+
+    let selection = create_signal(cx, String::new());
+
+    view! { cx,
+        div(class = "container") {
+            div(class="d-flex justify-content-center") {
+                div(class="col-3"){
+                    TreeView(state=state, row=Box::new(|cx: Scope, row: RowContext<(), Uuid>| view! { cx,
+                        span { (row.node.name) }
+                        span(class="badge bg-primary rounded-pill ms-auto") { (row.depth) }
+                    }), loader=Box::new(|parent: Uuid| Box::pin(load_children(parent))), on_select=Box::new(|nodes: Vec<Node<(), Uuid>>| {
+                        let names: Vec<String> = nodes.into_iter().map(|n| n.name).collect();
+                        selection.set(names.join(", "));
+                    }))
+                    p(class="mt-2 text-muted") { "Selected: " (selection.get()) }
+                }
+            }
+        }
+    }
+}
+/// Stands in for a server round trip fetching a node's children.
+async fn load_children(parent: Uuid) -> LoadResult<(), Uuid> {
+    TimeoutFuture::new(800).await;
+    Ok((1..=3)
+        .map(|i| Node::new(Uuid::new_v4(), Some(parent), &format!("Loaded {}", i)))
+        .collect())
+}
+
+// modified draggable
+
+fn main() {
+    console_error_panic_hook::set_once();
+    console_log::init_with_level(log::Level::Debug).unwrap();
+    sycamore::render(|cx| {
+        view! { cx,
+            Suspense(fallback=view! { cx,
+                div(class="d-flex justify-content-center") {
+                    div(class="spinner-border text-primary", role="status")
+                }
+            }) {
+                ContainerWidget()
+            }
+        }
+    });
+}
@@ -0,0 +1,48 @@
+use sycamore::prelude::*;
+use sycatree::components::{NodeState, SearchBox, TreeView, VirtualScroll};
+use sycatree::model::Node;
+
+/// 100 roots with 100 children of 10 leaves each: 101,100 nodes.
+fn large_tree() -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut next_id = 1;
+    let mut add = |parent_id: Option<i32>, name: String| {
+        let id = next_id;
+        next_id += 1;
+        nodes.push(Node::new(id, parent_id, &name));
+        id
+    };
+    for r in 0..100 {
+        let root = add(None, format!("Region {}", r));
+        for c in 0..100 {
+            let child = add(Some(root), format!("Office {}.{}", r, c));
+            for l in 0..10 {
+                add(Some(child), format!("Team {}.{}.{}", r, c, l));
+            }
+        }
+    }
+    nodes
+}
+
+#[component]
+fn App<G: Html>(cx: Scope) -> View<G> {
+    let state = create_ref(cx, NodeState::new(large_tree()));
+
+    view! { cx,
+        div(class="container py-4") {
+            div(class="card", style="width: 24rem;") {
+                SearchBox(state=state, placeholder="Find a team or office")
+                TreeView(state=state, aria_label="Organisation", virtual_scroll=VirtualScroll {
+                    height: 600.0,
+                    row_height: 36.0,
+                })
+            }
+        }
+    }
+}
+
+fn main() {
+    console_error_panic_hook::set_once();
+    console_log::init_with_level(log::Level::Debug).unwrap();
+    sycamore::render(App);
+}
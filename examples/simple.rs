@@ -0,0 +1,54 @@
+use sycamore::prelude::*;
+use sycatree::components::{NodeState, TreeView};
+use sycatree::model::NodeList;
+use sycatree::nested::TreeNode;
+
+#[component]
+fn App<G: Html>(cx: Scope) -> View<G> {
+    // The same tree a backend would send as nested JSON.
+    let roots: Vec<TreeNode> = serde_json::from_str(
+        r#"[
+            { "id": 1, "name": "Node 1", "children": [
+                { "id": 2, "name": "Node 2", "children": [
+                    { "id": 3, "name": "Node 3", "children": [
+                        { "id": 4, "name": "Node 4" }
+                    ] }
+                ] },
+                { "id": 5, "name": "Node 5" }
+            ] },
+            { "id": 6, "name": "Node 6" },
+            { "id": 7, "name": "Node 7", "children": [
+                { "id": 9, "name": "Node 9", "children": [
+                    { "id": 10, "name": "node 10" }
+                ] }
+            ] },
+            { "id": 8, "name": "Node 8" }
+        ]"#,
+    )
+    .unwrap();
+    let nodes: NodeList = NodeList::from_tree(roots).unwrap();
+
+    provide_context(cx, NodeState::new(nodes));
+
+    view! { cx,
+            div(class="py-4"){
+                div(class="container-sm"){
+                div(class="row align-items-center"){
+                    div(class="col"){
+                        div (class="card", style="width: 18rem;") {
+                            TreeView::<_, (), i32>(draggable=false)
+                        }
+                    }
+
+                }
+
+            }
+            }
+    }
+}
+
+fn main() {
+    console_error_panic_hook::set_once();
+    console_log::init_with_level(log::Level::Debug).unwrap();
+    sycamore::render(App);
+}
@@ -0,0 +1,1412 @@
+// https://htmldom.dev/make-a-draggable-element/
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::future::Future;
+use std::ops::Range;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use gloo::console::log;
+use gloo::timers::callback::Timeout;
+use sycamore::prelude::*;
+use sycamore::suspense::Suspense;
+use wasm_bindgen::*;
+use web_sys::{DataTransfer, Event, HtmlElement, KeyboardEvent, MouseEvent};
+
+use crate::history::{Edit, History};
+use crate::keyboard::{self, KeyAction};
+use crate::model::{DropPosition, EditError, Node, NodeId, NodeList, TreeError};
+use crate::search::{self, MatchMode, SearchQuery, SearchResults};
+
+/// Shared state of one tree. Hand it to [`TreeView`] as a prop or provide it as context.
+#[derive(Debug, Clone)]
+pub struct NodeState<T = (), I = i32> {
+    pub nodes: RcSignal<NodeList<T, I>>,
+    /// The nodes being dragged, in tree order, if the drag started in this tree.
+    pub dragged: RcSignal<Vec<I>>,
+    /// Ids of the nodes whose children are shown.
+    pub expanded: RcSignal<HashSet<I>>,
+    /// The row that has (or last had) keyboard focus.
+    pub focused: RcSignal<Option<I>>,
+    /// A node cut with Ctrl+X, waiting to be pasted into, after or before the
+    /// focused row with Ctrl+V, Shift+Ctrl+V or Alt+Ctrl+V.
+    pub cut: RcSignal<Option<I>>,
+    /// Ids of the selected nodes.
+    pub selected: RcSignal<HashSet<I>>,
+    /// The node a Shift-click range starts from: the last one clicked without Shift.
+    pub anchor: RcSignal<Option<I>>,
+    /// The node whose name is being edited inline.
+    pub editing: RcSignal<Option<I>>,
+    /// Undo/redo steps for the changes made through [`NodeState::mutate`]. Holds
+    /// [`DEFAULT_HISTORY_LIMIT`](crate::history::DEFAULT_HISTORY_LIMIT) steps unless
+    /// changed with [`History::set_limit`].
+    pub history: RcSignal<History<T, I>>,
+    /// Ids of the nodes added, changed or removed by the latest change made through
+    /// this state, e.g. to save just those. See [`sync_to_store`](crate::persist::sync_to_store).
+    pub changed: RcSignal<Vec<I>>,
+    /// Filters the tree to matching nodes and their ancestors. Usually set from
+    /// a [`SearchBox`].
+    pub search: RcSignal<SearchQuery>,
+}
+
+impl<T, I: NodeId> NodeState<T, I> {
+    /// Takes a `Vec` of nodes or a ready [`NodeList`], such as one from
+    /// [`NodeList::from_tree`]. The nodes are trusted to form a tree; see
+    /// [`try_new`](NodeState::try_new).
+    pub fn new(nodes: impl Into<NodeList<T, I>>) -> NodeState<T, I> {
+        NodeState {
+            nodes: create_rc_signal(nodes.into()),
+            dragged: create_rc_signal(Vec::new()),
+            expanded: create_rc_signal(HashSet::new()),
+            focused: create_rc_signal(None),
+            cut: create_rc_signal(None),
+            selected: create_rc_signal(HashSet::new()),
+            anchor: create_rc_signal(None),
+            editing: create_rc_signal(None),
+            history: create_rc_signal(History::default()),
+            changed: create_rc_signal(Vec::new()),
+            search: create_rc_signal(SearchQuery::default()),
+        }
+    }
+
+    /// Like [`new`](NodeState::new), but fails with every problem found if the
+    /// nodes don't form a tree, e.g. ones loaded from storage or the network.
+    pub fn try_new(nodes: Vec<Node<T, I>>) -> Result<NodeState<T, I>, Vec<TreeError<I>>> {
+        NodeList::try_new(nodes).map(NodeState::new)
+    }
+
+    /// Matches for the current [`search`](NodeState::search), or `None` while
+    /// there is nothing to search for or the regex is invalid.
+    pub fn search_results(&self) -> Option<SearchResults<I>> {
+        let query = self.search.get();
+        if query.is_empty() {
+            return None;
+        }
+        let matcher = query.matcher().ok()?;
+        Some(self.nodes.get().search(&matcher))
+    }
+
+    pub fn is_expanded(&self, id: I) -> bool {
+        self.expanded.get().contains(&id)
+    }
+
+    pub fn expand(&self, id: I) {
+        self.expanded.modify().insert(id);
+    }
+
+    pub fn collapse(&self, id: I) {
+        self.expanded.modify().remove(&id);
+    }
+
+    pub fn toggle(&self, id: I) {
+        if self.is_expanded(id) {
+            self.collapse(id)
+        } else {
+            self.expand(id)
+        }
+    }
+
+    pub fn is_selected(&self, id: I) -> bool {
+        self.selected.get().contains(&id)
+    }
+
+    /// Makes `id` the only selected node.
+    pub fn select(&self, id: I) {
+        self.selected.set(HashSet::from([id]));
+        self.anchor.set(Some(id));
+    }
+
+    /// Adds `id` to or removes it from the selection, keeping the rest.
+    pub fn toggle_selected(&self, id: I) {
+        let mut selected = self.selected.modify();
+        if !selected.remove(&id) {
+            selected.insert(id);
+        }
+        self.anchor.set(Some(id));
+    }
+
+    pub fn clear_selection(&self) {
+        self.selected.set(HashSet::new());
+        self.anchor.set(None);
+    }
+}
+
+impl<T: Clone, I: NodeId> NodeState<T, I> {
+    /// Ids of the rows on screen, top to bottom.
+    pub fn visible_ids(&self) -> Vec<I> {
+        let results = self.search_results();
+        self.nodes
+            .get()
+            .visible_ids(&self.expanded.get(), results.as_ref().map(|r| &r.shown))
+    }
+
+    /// Selects the visible rows from the anchor to `id`, both included.
+    pub fn select_range(&self, id: I) {
+        let Some(anchor) = *self.anchor.get_untracked() else {
+            return self.select(id);
+        };
+        let visible = untrack(|| self.visible_ids());
+        let (Some(from), Some(to)) = (
+            visible.iter().position(|&v| v == anchor),
+            visible.iter().position(|&v| v == id),
+        ) else {
+            return self.select(id);
+        };
+        let range = if from <= to { from..=to } else { to..=from };
+        self.selected.set(visible[range].iter().copied().collect());
+    }
+}
+
+impl<T: Clone + PartialEq, I: NodeId> NodeState<T, I> {
+    /// Changes the nodes as one undo step. All tree edits should go through here
+    /// rather than `nodes.modify()` so they can be undone.
+    pub fn mutate<R>(&self, f: impl FnOnce(&mut NodeList<T, I>) -> R) -> R {
+        let (result, edit) = self.write(|nodes| Edit::record(nodes, f));
+        if let Some(edit) = edit {
+            self.history.modify().record(edit);
+        }
+        result
+    }
+
+    /// Reverts the last change. Returns `false` if there was nothing to undo.
+    pub fn undo(&self) -> bool {
+        if !self.history.get_untracked().can_undo() {
+            return false;
+        }
+        self.write(|nodes| self.history.modify().undo(nodes));
+        self.prune();
+        true
+    }
+
+    /// Re-applies the last undone change. Returns `false` if there was nothing to redo.
+    pub fn redo(&self) -> bool {
+        if !self.history.get_untracked().can_redo() {
+            return false;
+        }
+        self.write(|nodes| self.history.modify().redo(nodes));
+        self.prune();
+        true
+    }
+
+    /// Adds the loaded children of a lazy node. Loading isn't an edit, so it
+    /// can't be undone.
+    pub fn merge_children(&self, id: I, children: Vec<Node<T, I>>) -> Result<(), EditError<I>> {
+        self.write(|nodes| nodes.merge_children(id, children))
+    }
+
+    /// Runs `f` on the nodes and publishes the ids it touched on
+    /// [`changed`](NodeState::changed).
+    fn write<R>(&self, f: impl FnOnce(&mut NodeList<T, I>) -> R) -> R {
+        let (result, changes) = self.nodes.modify().record(f);
+        if !changes.is_empty() {
+            let ids: HashSet<I> = changes.iter().map(|change| change.id()).collect();
+            self.changed.set(ids.into_iter().collect());
+        }
+        result
+    }
+
+    /// Deletes `id` with its subtree.
+    pub fn delete(&self, id: I) -> Result<Vec<Node<T, I>>, EditError<I>> {
+        let removed = self.mutate(|nodes| nodes.delete(id))?;
+        self.prune();
+        Ok(removed)
+    }
+
+    /// Sets the name of `id`.
+    pub fn rename(&self, id: I, name: &str) -> Result<(), EditError<I>> {
+        self.mutate(|nodes| nodes.rename(id, name))
+    }
+
+    /// Forgets the row state held for nodes that no longer exist.
+    fn prune(&self) {
+        let nodes = self.nodes.get_untracked();
+        self.selected.modify().retain(|&id| nodes.contains(id));
+        self.expanded.modify().retain(|&id| nodes.contains(id));
+        for signal in [&self.focused, &self.anchor, &self.cut, &self.editing] {
+            if signal.get_untracked().is_some_and(|id| !nodes.contains(id)) {
+                signal.set(None);
+            }
+        }
+    }
+}
+
+impl<T, I: NodeId> Default for NodeState<T, I> {
+    fn default() -> Self {
+        NodeState::new(Vec::new())
+    }
+}
+
+/// Renders a row's label from its node.
+pub type LabelFn<'a, T, I = i32> = Box<dyn Fn(&Node<T, I>) -> String + 'a>;
+
+/// Renders a row's content next to the built-in expand/collapse icon.
+pub type RowFn<'a, G, T, I = i32> =
+    Box<dyn for<'b> Fn(Scope<'b>, RowContext<T, I>) -> View<G> + 'a>;
+
+/// Checks a new name for the node with the given id, returning the message to show
+/// when it is rejected. Defaults to [`NodeList::validate_name`].
+pub type ValidateFn<'a, T, I = i32> =
+    Box<dyn Fn(&NodeList<T, I>, I, &str) -> Result<(), String> + 'a>;
+
+/// The children of a lazy node, or why they couldn't be fetched.
+pub type LoadResult<T, I = i32> = Result<Vec<Node<T, I>>, Box<dyn std::error::Error>>;
+
+/// Fetches the children of a lazy node, given its id. A node whose fetch fails
+/// stays lazy and collapses, so expanding it again retries.
+pub type LoadFn<'a, T, I = i32> =
+    Box<dyn Fn(I) -> Pin<Box<dyn Future<Output = LoadResult<T, I>>>> + 'a>;
+
+/// Called with the selected nodes, in tree order, whenever the selection changes.
+pub type SelectFn<'a, T, I = i32> = Box<dyn Fn(Vec<Node<T, I>>) + 'a>;
+
+/// What a [`RowFn`] gets to render one row with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowContext<T = (), I = i32> {
+    pub node: Node<T, I>,
+    /// 0 for root nodes.
+    pub depth: usize,
+    pub expanded: bool,
+    pub selected: bool,
+    /// Byte ranges of `node.name` matching the search, to pass to
+    /// [`search::highlight`]. Empty when not searching.
+    pub highlight: Vec<Range<usize>>,
+}
+
+/// The `TreeView` props every row needs, shared by reference down the tree.
+struct TreeConfig<'a, G: GenericNode, T, I> {
+    draggable: bool,
+    keyed: bool,
+    label: Option<LabelFn<'a, T, I>>,
+    row: Option<RowFn<'a, G, T, I>>,
+    validate: Option<ValidateFn<'a, T, I>>,
+    loader: Option<LoadFn<'a, T, I>>,
+    virtual_scroll: Option<VirtualScroll>,
+    /// The one row reachable with Tab (roving tabindex).
+    tab_stop: &'a ReadSignal<Option<I>>,
+    /// Where the context menu is open, if it is.
+    menu: &'a Signal<Option<MenuAt<I>>>,
+    results: &'a ReadSignal<Option<SearchResults<I>>>,
+}
+
+/// Virtual scrolling settings: the tree is shown in a scrollable viewport of fixed
+/// height and only the rows in view are mounted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VirtualScroll {
+    /// Viewport height in pixels.
+    pub height: f64,
+    /// Height of every row in pixels. Row content taller than this is clipped.
+    pub row_height: f64,
+}
+
+/// Rows mounted above and below the viewport so fast scrolling doesn't show gaps.
+const OVERSCAN_ROWS: usize = 5;
+
+/// The slice of the flattened tree that is mounted.
+#[derive(Debug, Clone, PartialEq)]
+struct RowWindow<T, I> {
+    /// Index of the first mounted row.
+    first: usize,
+    /// Number of visible rows in the whole tree.
+    total: usize,
+    rows: Vec<(Node<T, I>, usize)>,
+}
+
+/// A context menu opened on a row, at viewport coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MenuAt<I> {
+    id: I,
+    x: i32,
+    y: i32,
+}
+
+/// Characters typed in quick succession, matched against row names.
+#[derive(Default)]
+struct TypeAhead {
+    typed: String,
+    reset: Option<Timeout>,
+}
+
+const TYPE_AHEAD_RESET_MS: u32 = 500;
+
+/// Appends `key` to the typed prefix and returns the prefix so far. The prefix
+/// is cleared once no key is typed for `TYPE_AHEAD_RESET_MS`.
+fn type_ahead_push(type_ahead: &Rc<RefCell<TypeAhead>>, key: &str) -> String {
+    let mut state = type_ahead.borrow_mut();
+    state.typed.push_str(key);
+    let weak = Rc::downgrade(type_ahead);
+    state.reset = Some(Timeout::new(TYPE_AHEAD_RESET_MS, move || {
+        if let Some(type_ahead) = weak.upgrade() {
+            type_ahead.borrow_mut().typed.clear();
+        }
+    }));
+    state.typed.clone()
+}
+
+#[derive(Prop)]
+pub struct TreeViewProps<'a, G: GenericNode, T: 'static, I: 'static> {
+    /// Falls back to the `NodeState` from context when not given.
+    #[builder(default, setter(strip_option))]
+    state: Option<&'a NodeState<T, I>>,
+    #[builder(default = true)]
+    draggable: bool,
+    /// Render rows with `Keyed` so they keep their state across moves; `false` uses `Indexed`.
+    #[builder(default = true)]
+    keyed: bool,
+    /// Row label built from the node's payload. Defaults to the node's `name`.
+    #[builder(default, setter(strip_option))]
+    label: Option<LabelFn<'a, T, I>>,
+    /// Custom row template; takes precedence over `label`.
+    #[builder(default, setter(strip_option))]
+    row: Option<RowFn<'a, G, T, I>>,
+    /// Rename check, used instead of [`NodeList::validate_name`].
+    #[builder(default, setter(strip_option))]
+    validate: Option<ValidateFn<'a, T, I>>,
+    #[builder(default, setter(strip_option))]
+    on_select: Option<SelectFn<'a, T, I>>,
+    /// Loads the children of nodes marked [`lazy`](Node::lazy) when they are first expanded.
+    #[builder(default, setter(strip_option))]
+    loader: Option<LoadFn<'a, T, I>>,
+    /// Render the tree as a flat, virtually scrolled list. Meant for very large trees.
+    #[builder(default, setter(strip_option))]
+    virtual_scroll: Option<VirtualScroll>,
+    /// Accessible name of the tree.
+    #[builder(default = "Tree")]
+    aria_label: &'a str,
+}
+
+#[component]
+pub fn TreeView<'a, G: Html, T: Clone + PartialEq + Default + 'static, I: NodeId>(
+    cx: Scope<'a>,
+    props: TreeViewProps<'a, G, T, I>,
+) -> View<G> {
+    let state = props
+        .state
+        .unwrap_or_else(|| use_context::<NodeState<T, I>>(cx));
+    let results = create_memo(cx, || state.search_results());
+    let tab_stop = create_memo(cx, || {
+        let results = results.get();
+        let visible = state.nodes.get().visible_ids(
+            &state.expanded.get(),
+            results.as_ref().as_ref().map(|r| &r.shown),
+        );
+        state
+            .focused
+            .get()
+            .filter(|id| visible.contains(id))
+            .or_else(|| visible.first().copied())
+    });
+    let config = create_ref(
+        cx,
+        TreeConfig {
+            draggable: props.draggable,
+            keyed: props.keyed,
+            label: props.label,
+            row: props.row,
+            validate: props.validate,
+            loader: props.loader,
+            virtual_scroll: props.virtual_scroll,
+            tab_stop,
+            menu: create_signal(cx, None),
+            results,
+        },
+    );
+    let root_nodes = create_memo(cx, || {
+        let results = results.get();
+        state
+            .nodes
+            .get()
+            .children(None)
+            .filter(|n| is_shown(&results, n.id))
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+
+    // Open the way to every match.
+    create_effect(cx, || {
+        if let Some(results) = results.get().as_ref() {
+            let closed = !results.ancestors.is_subset(&state.expanded.get_untracked());
+            if closed {
+                state
+                    .expanded
+                    .modify()
+                    .extend(results.ancestors.iter().copied());
+            }
+        }
+    });
+
+    if let Some(on_select) = props.on_select {
+        let mut initial = true;
+        create_effect(cx, move || {
+            let selected = state.selected.get();
+            if std::mem::take(&mut initial) {
+                return;
+            }
+            let nodes = state.nodes.get_untracked();
+            on_select(
+                nodes
+                    .children(None)
+                    .flat_map(|root| std::iter::once(root).chain(nodes.descendants(root.id)))
+                    .filter(|n| selected.contains(&n.id))
+                    .cloned()
+                    .collect(),
+            );
+        });
+    }
+
+    let announcement = create_signal(cx, String::new());
+    let name_of = move |id: I| {
+        state
+            .nodes
+            .get_untracked()
+            .get(id)
+            .map_or_else(String::new, |n| n.name.clone())
+    };
+    let undo = move || {
+        if state.undo() {
+            announcement.set("Undone".to_owned());
+        }
+    };
+    let redo = move || {
+        if state.redo() {
+            announcement.set("Redone".to_owned());
+        }
+    };
+    // Ctrl+X / Ctrl+V move a node without drag-and-drop. Ctrl+V nests the cut node
+    // inside the focused one, Shift+Ctrl+V puts it right after and Alt+Ctrl+V right
+    // before, so pasting next to a root row moves it to the root level.
+    let shortcut = move |key: &str, shift: bool, alt: bool, focused: I| match key {
+        "z" if shift => {
+            redo();
+            true
+        }
+        "z" => {
+            undo();
+            true
+        }
+        "y" => {
+            redo();
+            true
+        }
+        "x" => {
+            state.cut.set(Some(focused));
+            announcement.set(format!("Cut {}", name_of(focused)));
+            true
+        }
+        "v" => {
+            let Some(cut) = *state.cut.get_untracked() else {
+                return false;
+            };
+            let (position, place) = if shift {
+                (DropPosition::After, "after")
+            } else if alt {
+                (DropPosition::Before, "before")
+            } else {
+                (DropPosition::Inside, "into")
+            };
+            match state.mutate(|nodes| nodes.drop_node(cut, focused, position)) {
+                Ok(()) => {
+                    state.cut.set(None);
+                    if position == DropPosition::Inside {
+                        state.expand(focused);
+                    }
+                    state.focused.set(Some(cut));
+                    announcement.set(format!(
+                        "Moved {} {} {}",
+                        name_of(cut),
+                        place,
+                        name_of(focused)
+                    ));
+                }
+                Err(err) => announcement.set(format!(
+                    "Can't move {} {} {}: {}",
+                    name_of(cut),
+                    place,
+                    name_of(focused),
+                    err
+                )),
+            }
+            true
+        }
+        _ => false,
+    };
+
+    let type_ahead = Rc::new(RefCell::new(TypeAhead::default()));
+    let handle_keydown = move |e: Event| {
+        let event: &KeyboardEvent = e.unchecked_ref();
+        let Some(focused) = *state.focused.get_untracked() else {
+            return;
+        };
+        let key = event.key();
+        if event.ctrl_key() || event.meta_key() {
+            if shortcut(
+                &key.to_lowercase(),
+                event.shift_key(),
+                event.alt_key(),
+                focused,
+            ) {
+                e.prevent_default();
+            }
+            return;
+        }
+        if event.alt_key() {
+            return;
+        }
+        if key == "F2" {
+            state.editing.set(Some(focused));
+            e.prevent_default();
+            return;
+        }
+        if key == " " {
+            if event.shift_key() {
+                state.select_range(focused);
+            } else {
+                state.toggle_selected(focused);
+            }
+            e.prevent_default();
+            return;
+        }
+        if key == "Escape" && state.cut.get_untracked().is_some() {
+            state.cut.set(None);
+            announcement.set("Cut cancelled".to_owned());
+            return;
+        }
+        let nodes = state.nodes.get_untracked();
+        let expanded = state.expanded.get_untracked();
+        let results = results.get_untracked();
+        let shown = results.as_ref().as_ref().map(|r| &r.shown);
+        let action = if key.chars().count() == 1 && key != " " {
+            let typed = type_ahead_push(&type_ahead, &key);
+            keyboard::type_ahead(&nodes, &expanded, shown, focused, &typed).map(KeyAction::Focus)
+        } else {
+            keyboard::navigate(&nodes, &expanded, shown, focused, &key)
+        };
+        let Some(action) = action else {
+            return;
+        };
+        e.prevent_default();
+        match action {
+            KeyAction::Focus(id) => state.focused.set(Some(id)),
+            KeyAction::Expand(id) => state.expand(id),
+            KeyAction::Collapse(id) => state.collapse(id),
+        }
+    };
+
+    // Virtual scrolling flattens the visible rows and mounts only the window in view.
+    let scroll_top = create_signal(cx, 0.0);
+    let viewport_ref = create_node_ref(cx);
+    let flat = props.virtual_scroll.map(|viewport| {
+        let rows = create_memo(cx, || {
+            let results = results.get();
+            state
+                .nodes
+                .get()
+                .visible_rows(
+                    &state.expanded.get(),
+                    results.as_ref().as_ref().map(|r| &r.shown),
+                )
+                .into_iter()
+                .map(|row| (row.node.clone(), row.depth))
+                .collect::<Vec<_>>()
+        });
+        let window = create_memo(cx, move || {
+            let rows = rows.get();
+            let first = ((*scroll_top.get() / viewport.row_height) as usize)
+                .saturating_sub(OVERSCAN_ROWS)
+                .min(rows.len());
+            let count = (viewport.height / viewport.row_height).ceil() as usize + 2 * OVERSCAN_ROWS;
+            RowWindow {
+                first,
+                total: rows.len(),
+                rows: rows[first..(first + count).min(rows.len())].to_vec(),
+            }
+        });
+
+        // Keyboard focus can move to a row that isn't mounted; scroll it into view
+        // so it mounts and picks the focus up.
+        create_effect(cx, move || {
+            let Some(focused) = *state.focused.get() else {
+                return;
+            };
+            let Some(viewport_dom) = viewport_ref.try_get::<DomNode>() else {
+                return;
+            };
+            let Some(index) = rows
+                .get_untracked()
+                .iter()
+                .position(|(n, _)| n.id == focused)
+            else {
+                return;
+            };
+            let element: web_sys::Element = viewport_dom.unchecked_into();
+            let top = index as f64 * viewport.row_height;
+            let scroll = element.scroll_top() as f64;
+            if top < scroll {
+                element.set_scroll_top(top as i32);
+            } else if top + viewport.row_height > scroll + viewport.height {
+                element.set_scroll_top((top + viewport.row_height - viewport.height) as i32);
+            }
+        });
+        (viewport, window)
+    });
+
+    let tree_style = move || match flat {
+        // Padding stands in for the rows above the window, the height for all of them.
+        Some((viewport, window)) => {
+            let window = window.get();
+            format!(
+                "box-sizing: border-box; padding-top: {}px; height: {}px",
+                window.first as f64 * viewport.row_height,
+                window.total as f64 * viewport.row_height
+            )
+        }
+        None => String::new(),
+    };
+    let tree_rows = match flat {
+        Some((_, window)) => {
+            let rows = create_memo(cx, move || window.get().rows.clone());
+            view! { cx,
+                Keyed(
+                    iterable=rows,
+                    view=move |cx, (n, depth)| view! { cx, NestedNode(n=n, depth=depth, state=state, config=config) },
+                    // A node that moves to another depth is remounted, since depth is fixed per row.
+                    key=|(n, depth)| (n.id, *depth),
+                )
+            }
+        }
+        None => node_rows(cx, root_nodes, 0, state, config),
+    };
+    let tree = view! { cx,
+        ul(class="list-group", role="tree", aria-label=props.aria_label, aria-multiselectable="true",
+            style=tree_style(), on:keydown=handle_keydown) {
+            (tree_rows)
+        }
+    };
+    let tree = match props.virtual_scroll {
+        Some(viewport) => {
+            let handle_scroll = move |e: Event| {
+                let viewport: web_sys::Element = e.current_target().unwrap().unchecked_into();
+                scroll_top.set(viewport.scroll_top() as f64);
+            };
+            view! { cx,
+                div(ref=viewport_ref, class="overflow-auto", style=format!("height: {}px", viewport.height),
+                    on:scroll=handle_scroll) {
+                    (tree)
+                }
+            }
+        }
+        None => tree,
+    };
+
+    let no_matches = create_selector(cx, || {
+        results
+            .get()
+            .as_ref()
+            .as_ref()
+            .is_some_and(|r| r.matches.is_empty())
+    });
+
+    view! { cx,
+        (if *no_matches.get() {
+            view! { cx, div(class="text-muted small px-3 py-2") { "No matches" } }
+        } else {
+            view! { cx, }
+        })
+        (tree)
+        div(class="visually-hidden", aria-live="polite") {
+            (announcement.get())
+        }
+        (match *config.menu.get() {
+            Some(at) => view! { cx, ContextMenu(at=at, state=state, config=config) },
+            None => view! { cx, },
+        })
+    }
+}
+
+/// Row actions offered on right-click. Clicking anywhere outside closes it.
+#[component(inline_props)]
+fn ContextMenu<'a, G: Html, T: Clone + PartialEq + Default + 'static, I: NodeId>(
+    cx: Scope<'a>,
+    at: MenuAt<I>,
+    state: &'a NodeState<T, I>,
+    config: &'a TreeConfig<'a, G, T, I>,
+) -> View<G> {
+    let id = at.id;
+    let close = move || config.menu.set(None);
+    // New nodes start out in rename mode so they can be named right away.
+    let added = move |new_id: Result<I, EditError<I>>| match new_id {
+        Ok(new_id) => {
+            state.focused.set(Some(new_id));
+            state.editing.set(Some(new_id));
+        }
+        Err(err) => log!(format!("could not add a node: {}", err)),
+    };
+    let add_child = move |_| {
+        close();
+        let name = state
+            .nodes
+            .get_untracked()
+            .unique_name(Some(id), "New node");
+        let new_id = state.mutate(|nodes| nodes.add_child(Some(id), &name, T::default()));
+        state.expand(id);
+        added(new_id);
+    };
+    let add_sibling = move |_| {
+        close();
+        let nodes = state.nodes.get_untracked();
+        let parent_id = nodes.get(id).and_then(|n| n.parent_id);
+        let name = nodes.unique_name(parent_id, "New node");
+        added(state.mutate(|nodes| nodes.add_sibling(id, &name, T::default())));
+    };
+    let duplicate = move |_| {
+        close();
+        match state.mutate(|nodes| nodes.duplicate(id)) {
+            Ok(copy) => state.focused.set(Some(copy)),
+            Err(err) => log!(format!("could not duplicate {}: {}", id, err)),
+        }
+    };
+    let delete = move |_| {
+        close();
+        // Keep focus in the tree: on the row above, or the first one left.
+        let visible = untrack(|| state.visible_ids());
+        let above = visible
+            .iter()
+            .position(|&v| v == id)
+            .and_then(|i| i.checked_sub(1))
+            .map(|i| visible[i]);
+        if let Err(err) = state.delete(id) {
+            log!(format!("could not delete {}: {}", id, err));
+            return;
+        }
+        let next = above.or_else(|| state.nodes.get_untracked().child_ids(None).first().copied());
+        state.focused.set(next);
+    };
+    let handle_keydown = move |e: Event| {
+        let event: &KeyboardEvent = e.unchecked_ref();
+        if event.key() == "Escape" {
+            close();
+            state.focused.set(Some(id));
+        }
+    };
+
+    view! { cx,
+        div(class="position-fixed top-0 start-0 w-100 h-100", on:click=move |_| close(),
+            on:contextmenu=move |e: Event| { e.prevent_default(); close() })
+        ul(class="dropdown-menu show position-fixed", role="menu", on:keydown=handle_keydown,
+            style=format!("left: {}px; top: {}px", at.x, at.y)) {
+            li { button(class="dropdown-item", role="menuitem", on:click=add_child) { "Add child" } }
+            li { button(class="dropdown-item", role="menuitem", on:click=add_sibling) { "Add sibling" } }
+            li { button(class="dropdown-item", role="menuitem", on:click=duplicate) { "Duplicate" } }
+            li { hr(class="dropdown-divider") }
+            li { button(class="dropdown-item text-danger", role="menuitem", on:click=delete) { "Delete" } }
+        }
+    }
+}
+
+fn node_rows<'a, G: Html, T: Clone + PartialEq + 'static, I: NodeId>(
+    cx: Scope<'a>,
+    nodes: &'a ReadSignal<Vec<Node<T, I>>>,
+    depth: usize,
+    state: &'a NodeState<T, I>,
+    config: &'a TreeConfig<'a, G, T, I>,
+) -> View<G> {
+    if config.keyed {
+        view! { cx,
+            Keyed(
+                iterable=nodes,
+                view=move |cx, n| view! { cx, NestedNode(n=n, depth=depth, state=state, config=config) },
+                key=|n| n.id,
+            )
+        }
+    } else {
+        view! { cx,
+            Indexed(
+                iterable=nodes,
+                view=move |cx, n| view! { cx, NestedNode(n=n, depth=depth, state=state, config=config) },
+            )
+        }
+    }
+}
+
+fn is_shown<I: NodeId>(results: &Option<SearchResults<I>>, id: I) -> bool {
+    results.as_ref().is_none_or(|r| r.shown.contains(&id))
+}
+
+/// `name` with the `ranges` matching the search marked.
+fn highlighted<G: Html>(cx: Scope, name: &str, ranges: &[Range<usize>]) -> View<G> {
+    if ranges.is_empty() {
+        let name = name.to_owned();
+        return view! { cx, (name) };
+    }
+    View::new_fragment(
+        search::highlight(name, ranges)
+            .into_iter()
+            .map(|(text, hit)| {
+                let text = text.to_owned();
+                if hit {
+                    view! { cx, mark(class="p-0") { (text) } }
+                } else {
+                    view! { cx, (text) }
+                }
+            })
+            .collect(),
+    )
+}
+
+const SELECTED_CLASSES: &str = "bg-primary bg-opacity-10";
+const DROP_INDICATOR_CLASSES: &str =
+    "border border-top border-bottom border-2 border-primary bg-danger bg-opacity-25";
+const FORBIDDEN_DROP_CLASS: &str = "bg-danger bg-opacity-25";
+/// Marks the row being dragged, until the drag ends.
+const DRAGGING_CLASS: &str = "opacity-50";
+
+/// Works out which drop zone of the row under the pointer a drag event is in.
+fn drop_position(e: &Event) -> DropPosition {
+    let drag_event: &web_sys::DragEvent = e.unchecked_ref();
+    let row: web_sys::Element = e.current_target().unwrap().unchecked_into();
+    let rect = row.get_bounding_client_rect();
+    DropPosition::from_offset(drag_event.client_y() as f64 - rect.top(), rect.height())
+}
+
+fn drop_indicator_class(position: DropPosition) -> &'static str {
+    match position {
+        DropPosition::Before => "border-top border-2 border-primary",
+        DropPosition::Inside => "border border-2 border-primary",
+        DropPosition::After => "border-bottom border-2 border-primary",
+    }
+}
+
+fn clear_drop_indicator(dom: &DomNode) {
+    dom.remove_class(DROP_INDICATOR_CLASSES);
+}
+
+/// Dragged ids travel through the `DataTransfer` as a comma-separated list.
+fn join_ids<I: NodeId>(ids: &[I]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The ids written by [`join_ids`], or `None` if the drag didn't come from a tree.
+fn parse_ids<I: NodeId>(data: &str) -> Option<Vec<I>> {
+    data.split(',').map(|id| id.parse().ok()).collect()
+}
+
+/// Replaces the browser's drag image with a badge showing how many nodes are moved.
+fn set_count_drag_image(data_transfer: &DataTransfer, count: usize) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let badge: HtmlElement = document.create_element("span").unwrap().unchecked_into();
+    badge.set_class_name("badge bg-primary position-absolute top-0");
+    badge.set_inner_text(&format!("{} items", count));
+    // The image is taken from a rendered element, so park it off-screen
+    // for the snapshot and remove it right after.
+    badge.set_attribute("style", "left: -1000px").unwrap();
+    let body = document.body().unwrap();
+    body.append_child(&badge).unwrap();
+    data_transfer.set_drag_image(&badge, 0, 0);
+    Timeout::new(0, move || badge.remove()).forget();
+}
+
+#[component(inline_props)]
+fn NestedNode<'a, G: Html, T: Clone + PartialEq + 'static, I: NodeId>(
+    cx: Scope<'a>,
+    n: Node<T, I>,
+    depth: usize,
+    state: &'a NodeState<T, I>,
+    config: &'a TreeConfig<'a, G, T, I>,
+) -> View<G> {
+    let node_ref = create_node_ref(cx);
+    let item_ref = create_node_ref(cx);
+    let ns = &state.nodes;
+    let dragged = &state.dragged;
+    let id = n.id;
+    // Keyed lists reuse this component when the node moves or is renamed, so
+    // read the live node back from the list instead of trusting `n`.
+    let node_signal = create_memo(cx, move || ns.get().get(id).cloned().unwrap_or(n.clone()));
+    let children_signal = create_memo(cx, move || {
+        if config.virtual_scroll.is_some() {
+            // Children are rows of their own in the flat list.
+            return Vec::new();
+        }
+        let results = config.results.get();
+        ns.get()
+            .children(Some(id))
+            .filter(|n| is_shown(&results, n.id))
+            .cloned()
+            .collect::<Vec<Node<T, I>>>()
+    });
+    // A selector, so loading a lazy node's children doesn't rebuild the children block.
+    let has_child = create_selector(cx, move || match config.results.get().as_ref() {
+        // While searching, only children left in by the filter count.
+        Some(results) => ns
+            .get()
+            .children(Some(id))
+            .any(|n| results.shown.contains(&n.id)),
+        None => ns.get().has_children(id),
+    });
+    let highlight = create_memo(cx, move || {
+        config
+            .results
+            .get()
+            .as_ref()
+            .as_ref()
+            .and_then(|r| r.matches.get(&id).cloned())
+            .unwrap_or_default()
+    });
+    let toggle_state = create_memo(cx, move || state.expanded.get().contains(&id));
+
+    // 1-based position among siblings and the sibling count, for aria-posinset/-setsize.
+    let position = create_memo(cx, move || ns.get().sibling_position(id).unwrap_or((1, 1)));
+
+    let toggle = move |_| state.toggle(id);
+
+    let handle_focus = move |_| state.focused.set(Some(id));
+    let handle_click = move |e: Event| {
+        let event: &MouseEvent = e.unchecked_ref();
+        if event.shift_key() {
+            state.select_range(id);
+        } else if event.ctrl_key() || event.meta_key() {
+            state.toggle_selected(id);
+        } else {
+            state.select(id);
+        }
+    };
+    let handle_contextmenu = move |e: Event| {
+        let event: &MouseEvent = e.unchecked_ref();
+        e.prevent_default();
+        config.menu.set(Some(MenuAt {
+            id,
+            x: event.client_x(),
+            y: event.client_y(),
+        }));
+    };
+    let is_selected = create_memo(cx, move || state.selected.get().contains(&id));
+    let aria_selected = move || {
+        if *is_selected.get() {
+            "true"
+        } else {
+            "false"
+        }
+    };
+    let tabindex = move || {
+        if *config.tab_stop.get() == Some(id) {
+            "0"
+        } else {
+            "-1"
+        }
+    };
+
+    let class = move || {
+        format!(
+            "px-2 text-primary fa-regular {}",
+            if !*has_child.get() {
+                "mx-2"
+            } else if *toggle_state.get() {
+                "fa-square-minus"
+            } else {
+                "fa-square-plus"
+            }
+        )
+    };
+
+    let handle_dragstart = move |e: Event| {
+        let dom = node_ref.get::<DomNode>();
+        let drag_event_ref: &web_sys::DragEvent = e.unchecked_ref();
+        let drag_event = drag_event_ref.clone();
+        let data_transf: DataTransfer = drag_event.data_transfer().unwrap();
+        // Dragging a selected row takes the whole selection along.
+        let ids = if state.is_selected(id) {
+            ns.get().topmost(&state.selected.get())
+        } else {
+            vec![id]
+        };
+        data_transf.set_effect_allowed("move");
+        data_transf.set_data("text/html", &join_ids(&ids)).unwrap();
+        if ids.len() > 1 {
+            set_count_drag_image(&data_transf, ids.len());
+        }
+        dragged.set(ids);
+        dom.add_class(DRAGGING_CLASS);
+    };
+
+    let handle_dragover = move |e: Event| {
+        if !config.draggable {
+            return;
+        }
+        let dom = node_ref.get::<DomNode>();
+        e.stop_propagation();
+        clear_drop_indicator(&dom);
+        let position = drop_position(&e);
+        let dragged = dragged.get();
+        let allowed = dragged.is_empty()
+            || ns
+                .get()
+                .can_drop_all(&dragged, node_signal.get().id, position);
+        if allowed {
+            e.prevent_default();
+            dom.add_class(drop_indicator_class(position));
+        } else {
+            // Not cancelling dragover leaves the drop disabled; also show the row as forbidden.
+            let drag_event: &web_sys::DragEvent = e.unchecked_ref();
+            drag_event.data_transfer().unwrap().set_drop_effect("none");
+            dom.add_class(FORBIDDEN_DROP_CLASS);
+        }
+    };
+
+    let handle_dragleave = |e: Event| {
+        let dom = node_ref.get::<DomNode>();
+        e.prevent_default();
+        clear_drop_indicator(&dom);
+    };
+
+    let handle_dragend = |_: Event| {
+        let dom = node_ref.get::<DomNode>();
+        dragged.set(Vec::new());
+        dom.remove_class(DRAGGING_CLASS);
+    };
+    let handle_drop = move |e: Event| {
+        if !config.draggable {
+            return;
+        }
+        let dom = node_ref.get::<DomNode>();
+
+        let drag_event_ref: &web_sys::DragEvent = e.unchecked_ref();
+        let drag_event = drag_event_ref.clone();
+        let data = drag_event
+            .data_transfer()
+            .and_then(|data_transf| data_transf.get_data("text/html").ok())
+            .unwrap_or_default();
+
+        e.stop_propagation();
+        clear_drop_indicator(&dom);
+
+        let Some(dragged_ids) = parse_ids(&data) else {
+            log!(format!("ignoring drop of {:?}", data));
+            return;
+        };
+        let target_id = node_signal.get().id;
+        // A multi-node drop is a single undo step.
+        if let Err(err) =
+            state.mutate(|nodes| nodes.drop_nodes(&dragged_ids, target_id, drop_position(&e)))
+        {
+            log!(format!("drop refused: {}", err));
+        }
+    };
+
+    // In virtual scrolling mode rows are flat, fixed-height items indented by depth.
+    let (item_class, item_style) = match config.virtual_scroll {
+        Some(viewport) => (
+            "list-group-item d-flex align-items-center py-0 overflow-hidden",
+            format!(
+                "height: {}px; padding-left: {}rem",
+                viewport.row_height,
+                1.0 + depth as f64 * 1.5
+            ),
+        ),
+        None => ("list-group-item", String::new()),
+    };
+    let row_class = if config.virtual_scroll.is_some() {
+        "d-flex align-items-center flex-grow-1"
+    } else {
+        "d-flex align-items-center"
+    };
+
+    let is_editing = create_memo(cx, move || *state.editing.get() == Some(id));
+    let content = move || match (&config.row, &config.label) {
+        (Some(row), _) => row(
+            cx,
+            RowContext {
+                node: (*node_signal.get()).clone(),
+                depth,
+                expanded: *toggle_state.get(),
+                selected: *is_selected.get(),
+                highlight: (*highlight.get()).clone(),
+            },
+        ),
+        (None, Some(label)) => view! { cx, (label(&node_signal.get())) },
+        (None, None) => highlighted(cx, &node_signal.get().name, &highlight.get()),
+    };
+
+    let item = view! { cx,
+        li(ref=item_ref, class=item_class, style=item_style, role="treeitem", aria-level=(depth + 1).to_string(),
+            aria-posinset=position.get().0.to_string(), aria-setsize=position.get().1.to_string(),
+            aria-selected=aria_selected(), tabindex=tabindex(), on:focus=handle_focus) {
+            div(ref=node_ref, class=row_class, draggable=config.draggable, on:click=handle_click,
+                on:dblclick=move |_| state.editing.set(Some(id)), on:contextmenu=handle_contextmenu,
+                on:dragstart=handle_dragstart, on:dragend=handle_dragend,
+                on:dragover=handle_dragover, on:dragleave=handle_dragleave, on:drop=handle_drop) {
+                i(on:click=toggle, on:dblclick=|e: Event| e.stop_propagation(), class=class())
+                (if *is_editing.get() {
+                    view! { cx,
+                        RenameInput(id=id, name=node_signal.get_untracked().name.clone(), state=state, config=config)
+                    }
+                } else {
+                    content()
+                })
+            }
+            (if *toggle_state.get() && *has_child.get() {
+                if node_signal.get_untracked().lazy && config.loader.is_some() {
+                    view! { cx,
+                        Suspense(fallback=view! { cx,
+                            div(class="spinner-border spinner-border-sm text-primary ms-4", role="status") {
+                                span(class="visually-hidden") { "Loading..." }
+                            }
+                        }) {
+                            LoadChildren(id=id, children=children_signal, depth=depth + 1, state=state, config=config)
+                        }
+                    }
+                } else if config.virtual_scroll.is_some() {
+                    // The children are rows of their own in the flat list.
+                    view! { cx, }
+                } else {
+                    view! { cx,
+                        ul(class="list-group", role="group") {
+                            (node_rows(cx, children_signal, depth + 1, state, config))
+                        }
+                    }
+                }
+            } else {
+                view! { cx, }
+            })
+        }
+    };
+
+    // These effects touch the elements directly, so they go after the view is built.
+    // Leaves must not carry aria-expanded at all.
+    create_effect(cx, move || {
+        let item = item_ref.get::<DomNode>();
+        if *has_child.get() {
+            let expanded = if *toggle_state.get() { "true" } else { "false" };
+            item.set_attribute("aria-expanded", expanded);
+        } else {
+            item.remove_attribute("aria-expanded");
+        }
+    });
+    // Follow keyboard focus moves made through `state.focused`.
+    create_effect(cx, move || {
+        if *state.focused.get() == Some(id) {
+            let item: HtmlElement = item_ref.get::<DomNode>().unchecked_into();
+            if item.is_connected() {
+                item.focus().unwrap();
+            } else {
+                // A row mounted with focus (new, or scrolled into view) isn't in
+                // the document until this view is inserted.
+                Timeout::new(0, move || item.focus().unwrap()).forget();
+            }
+        }
+    });
+    create_effect(cx, move || {
+        let row = node_ref.get::<DomNode>();
+        if *is_selected.get() {
+            row.add_class(SELECTED_CLASSES);
+        } else {
+            row.remove_class(SELECTED_CLASSES);
+        }
+    });
+    create_effect(cx, move || {
+        let row = node_ref.get::<DomNode>();
+        if *state.cut.get() == Some(id) {
+            row.add_class("opacity-50");
+        } else {
+            row.remove_class("opacity-50");
+        }
+    });
+
+    item
+}
+
+/// Runs the loader for a lazy node, then shows the children it returned.
+#[component(inline_props)]
+async fn LoadChildren<'a, G: Html, T: Clone + PartialEq + 'static, I: NodeId>(
+    cx: Scope<'a>,
+    id: I,
+    children: &'a ReadSignal<Vec<Node<T, I>>>,
+    depth: usize,
+    state: &'a NodeState<T, I>,
+    config: &'a TreeConfig<'a, G, T, I>,
+) -> View<G> {
+    let loader = config.loader.as_ref().unwrap();
+    let loaded = loader(id).await;
+    // Merging can re-render the row that owns this component (e.g. when nothing was
+    // loaded), so leave it until this future has finished.
+    let owned = state.clone();
+    Timeout::new(0, move || {
+        let merged = loaded.and_then(|loaded| Ok(owned.merge_children(id, loaded)?));
+        if let Err(err) = merged {
+            log!(format!("could not load the children of {}: {}", id, err));
+            owned.collapse(id);
+        }
+    })
+    .forget();
+
+    if config.virtual_scroll.is_some() {
+        return view! { cx, };
+    }
+    view! { cx,
+        ul(class="list-group", role="group") {
+            (node_rows(cx, children, depth, state, config))
+        }
+    }
+}
+
+/// The inline name editor: Enter commits a valid name, Escape or leaving the field cancels.
+#[component(inline_props)]
+fn RenameInput<'a, G: Html, T: Clone + PartialEq + 'static, I: NodeId>(
+    cx: Scope<'a>,
+    id: I,
+    name: String,
+    state: &'a NodeState<T, I>,
+    config: &'a TreeConfig<'a, G, T, I>,
+) -> View<G> {
+    let input_ref = create_node_ref(cx);
+    let name = create_signal(cx, name);
+    let error = create_signal(cx, None::<String>);
+
+    let finish = move || {
+        state.editing.set(None);
+        // Hand focus back to the row.
+        state.focused.set(Some(id));
+    };
+    let commit = move || {
+        let name = name.get().trim().to_owned();
+        let nodes = state.nodes.get_untracked();
+        let valid = match &config.validate {
+            Some(validate) => validate(&nodes, id, &name),
+            None => nodes.validate_name(id, &name),
+        };
+        match valid {
+            Ok(()) => match state.rename(id, &name) {
+                Ok(()) => finish(),
+                Err(err) => error.set(Some(err.to_string())),
+            },
+            Err(message) => error.set(Some(message)),
+        }
+    };
+    let handle_keydown = move |e: Event| {
+        // Keep typing away from the tree's keyboard navigation.
+        e.stop_propagation();
+        let event: &KeyboardEvent = e.unchecked_ref();
+        match event.key().as_str() {
+            "Enter" => commit(),
+            "Escape" => finish(),
+            _ => {}
+        }
+    };
+    let handle_blur = move |_| {
+        // Removing the input after Enter/Escape can blur it too.
+        if *state.editing.get_untracked() == Some(id) {
+            state.editing.set(None);
+        }
+    };
+    let class = || {
+        if error.get().is_some() {
+            "form-control form-control-sm is-invalid"
+        } else {
+            "form-control form-control-sm"
+        }
+    };
+
+    let editor = view! { cx,
+        div(class="flex-grow-1") {
+            input(ref=input_ref, class=class(), aria-label="Name", bind:value=name,
+                on:keydown=handle_keydown, on:blur=handle_blur, on:click=|e: Event| e.stop_propagation())
+            div(class="invalid-feedback") { (error.get().as_deref().unwrap_or_default().to_owned()) }
+        }
+    };
+
+    // The input only lands in the document after this view is returned.
+    let input: HtmlElement = input_ref.get::<DomNode>().unchecked_into();
+    Timeout::new(0, move || input.focus().unwrap()).forget();
+
+    editor
+}
+
+#[derive(Prop)]
+pub struct SearchBoxProps<'a, T: 'static, I: 'static> {
+    /// Falls back to the `NodeState` from context when not given.
+    #[builder(default, setter(strip_option))]
+    state: Option<&'a NodeState<T, I>>,
+    #[builder(default = "Search")]
+    placeholder: &'a str,
+}
+
+/// A search input for the tree sharing its `NodeState`: the tree then shows only
+/// nodes whose names match, along with their ancestors, which are expanded.
+/// Buttons toggle case-sensitive, fuzzy and regex matching.
+#[component]
+pub fn SearchBox<'a, G: Html, T: 'static, I: NodeId>(
+    cx: Scope<'a>,
+    props: SearchBoxProps<'a, T, I>,
+) -> View<G> {
+    let state = props
+        .state
+        .unwrap_or_else(|| use_context::<NodeState<T, I>>(cx));
+    let text = create_signal(cx, state.search.get_untracked().text.clone());
+    create_effect(cx, || {
+        let text = text.get();
+        if *text != state.search.get_untracked().text {
+            state.search.modify().text = (*text).clone();
+        }
+    });
+    let error = create_memo(cx, || {
+        state
+            .search
+            .get()
+            .matcher()
+            .err()
+            .map(|err| err.to_string())
+    });
+
+    let toggle_mode = move |mode: MatchMode| {
+        let mut search = state.search.modify();
+        search.mode = if search.mode == mode {
+            MatchMode::Substring
+        } else {
+            mode
+        };
+    };
+    let pressed = |on: bool| if on { "true" } else { "false" };
+    let button_class = |on: bool| {
+        if on {
+            "btn btn-outline-secondary active"
+        } else {
+            "btn btn-outline-secondary"
+        }
+    };
+    let case_sensitive = create_selector(cx, || state.search.get().case_sensitive);
+    let fuzzy = create_selector(cx, || state.search.get().mode == MatchMode::Fuzzy);
+    let regex = create_selector(cx, || state.search.get().mode == MatchMode::Regex);
+
+    let handle_keydown = move |e: Event| {
+        let event: &KeyboardEvent = e.unchecked_ref();
+        if event.key() == "Escape" {
+            text.set(String::new());
+        }
+    };
+    let class = || {
+        if error.get().is_some() {
+            "form-control form-control-sm is-invalid"
+        } else {
+            "form-control form-control-sm"
+        }
+    };
+
+    view! { cx,
+        div(class="input-group input-group-sm has-validation p-2", role="search") {
+            input(type="search", class=class(), placeholder=props.placeholder, aria-label=props.placeholder,
+                bind:value=text, on:keydown=handle_keydown)
+            button(type="button", class=button_class(*case_sensitive.get()), title="Match case",
+                aria-pressed=pressed(*case_sensitive.get()),
+                on:click=move |_| {
+                    let mut search = state.search.modify();
+                    search.case_sensitive = !search.case_sensitive;
+                }) { "Aa" }
+            button(type="button", class=button_class(*fuzzy.get()), title="Fuzzy match",
+                aria-pressed=pressed(*fuzzy.get()), on:click=move |_| toggle_mode(MatchMode::Fuzzy)) { "~" }
+            button(type="button", class=button_class(*regex.get()), title="Regular expression",
+                aria-pressed=pressed(*regex.get()), on:click=move |_| toggle_mode(MatchMode::Regex)) { ".*" }
+            div(class="invalid-feedback") { (error.get().as_deref().unwrap_or_default().to_owned()) }
+        }
+    }
+}
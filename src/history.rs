@@ -0,0 +1,246 @@
+//! Undo/redo for changes to a [`NodeList`].
+//!
+//! Every change is recorded as an [`Edit`]: the operations it made on the list,
+//! each holding what it overwrote (a node's old parent and rank, its old name,
+//! a removed node). Undoing applies their inverses last first, so a deleted
+//! subtree comes back in its old place, and a change touching many nodes (such
+//! as a multi-node drop) is one undo step. Recording and undoing cost as much
+//! as the edit itself, not the size of the list.
+
+use std::collections::VecDeque;
+
+use crate::model::{Change, NodeId, NodeList};
+
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// One undoable change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edit<T = (), I = i32> {
+    changes: Vec<Change<T, I>>,
+}
+
+impl<T: Clone + PartialEq, I: NodeId> Edit<T, I> {
+    /// Runs `f` on `nodes`, returning its result and the edit it made, or `None`
+    /// if it changed nothing.
+    pub fn record<R>(
+        nodes: &mut NodeList<T, I>,
+        f: impl FnOnce(&mut NodeList<T, I>) -> R,
+    ) -> (R, Option<Edit<T, I>>) {
+        let (result, changes) = nodes.record(f);
+        let edit = Edit { changes };
+        let changed = !edit.is_noop(nodes);
+        (result, changed.then_some(edit))
+    }
+
+    /// Undoes the edit on `nodes`, turning it into the edit that redoes it.
+    pub fn revert(&mut self, nodes: &mut NodeList<T, I>) {
+        let changes = std::mem::take(&mut self.changes);
+        self.changes = nodes.revert(changes);
+    }
+
+    /// Whether every node the edit touched is back as it was, e.g. a rename to
+    /// the same name.
+    fn is_noop(&self, nodes: &NodeList<T, I>) -> bool {
+        let mut seen = std::collections::HashSet::new();
+        self.changes.iter().all(|change| match change {
+            Change::Rank { .. } => false,
+            // The first change to a node holds its version from before the edit.
+            Change::Put { id, old } => !seen.insert(*id) || nodes.get(*id) == old.as_ref(),
+        })
+    }
+}
+
+/// Undo and redo stacks, keeping at most `limit` undo steps.
+#[derive(Debug, Clone)]
+pub struct History<T = (), I = i32> {
+    undo: VecDeque<Edit<T, I>>,
+    redo: Vec<Edit<T, I>>,
+    limit: usize,
+}
+
+impl<T, I> Default for History<T, I> {
+    fn default() -> Self {
+        History::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl<T, I> History<T, I> {
+    pub fn new(limit: usize) -> History<T, I> {
+        History {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Changes the depth limit, dropping the oldest steps if there are too many.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.trim();
+    }
+
+    /// Adds a new step. Anything that could be redone is discarded.
+    pub fn record(&mut self, edit: Edit<T, I>) {
+        self.redo.clear();
+        self.undo.push_back(edit);
+        self.trim();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    fn trim(&mut self) {
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+}
+
+impl<T: Clone + PartialEq, I: NodeId> History<T, I> {
+    /// Reverts the latest step on `nodes`. Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self, nodes: &mut NodeList<T, I>) -> bool {
+        let Some(mut edit) = self.undo.pop_back() else {
+            return false;
+        };
+        edit.revert(nodes);
+        self.redo.push(edit);
+        true
+    }
+
+    /// Re-applies the latest undone step. Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self, nodes: &mut NodeList<T, I>) -> bool {
+        let Some(mut edit) = self.redo.pop() else {
+            return false;
+        };
+        edit.revert(nodes);
+        self.undo.push_back(edit);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Node;
+    use crate::testing::{names, roots, sample};
+
+    fn edit(history: &mut History, nodes: &mut NodeList, f: impl FnOnce(&mut NodeList)) {
+        let ((), edit) = Edit::record(nodes, f);
+        history.record(edit.unwrap());
+    }
+
+    fn assert_same(nodes: &NodeList, expected: &NodeList) {
+        assert_eq!(nodes.len(), expected.len());
+        for node in expected.iter() {
+            assert_eq!(nodes.get(node.id), Some(node));
+        }
+    }
+
+    #[test]
+    fn undoing_a_delete_puts_the_subtree_back_in_place() {
+        let mut nodes = sample();
+        let original = nodes.clone();
+        let mut history = History::default();
+        edit(&mut history, &mut nodes, |n| {
+            n.delete(2).unwrap();
+        });
+        assert_eq!(names(&nodes, Some(1)), ["c"]);
+
+        assert!(history.undo(&mut nodes));
+        assert_eq!(names(&nodes, Some(1)), ["b", "c"]);
+        assert_eq!(names(&nodes, Some(2)), ["d", "e"]);
+        assert_same(&nodes, &original);
+
+        assert!(history.redo(&mut nodes));
+        assert_eq!(names(&nodes, Some(1)), ["c"]);
+        assert!(!history.redo(&mut nodes));
+    }
+
+    #[test]
+    fn undo_and_redo_replay_moves_and_renames() {
+        let mut nodes = sample();
+        let original = nodes.clone();
+        let mut history = History::default();
+        edit(&mut history, &mut nodes, |n| n.move_before(3, 2).unwrap());
+        edit(&mut history, &mut nodes, |n| n.rename(2, "B").unwrap());
+        assert_eq!(names(&nodes, Some(1)), ["c", "B"]);
+        let edited = nodes.clone();
+
+        assert!(history.undo(&mut nodes));
+        assert_eq!(names(&nodes, Some(1)), ["c", "b"]);
+        assert!(history.undo(&mut nodes));
+        assert!(!history.undo(&mut nodes));
+        assert_same(&nodes, &original);
+
+        assert!(history.redo(&mut nodes));
+        assert!(history.redo(&mut nodes));
+        assert_same(&nodes, &edited);
+    }
+
+    #[test]
+    fn undo_restores_ranks_after_renumbering() {
+        let mut nodes = roots(30);
+        let original = nodes.clone();
+        let mut history = History::default();
+        for id in 3..=30 {
+            edit(&mut history, &mut nodes, |n| n.move_before(id, 2).unwrap());
+        }
+        while history.undo(&mut nodes) {}
+        assert_same(&nodes, &original);
+        assert_eq!(nodes.child_ids(None), original.child_ids(None));
+    }
+
+    #[test]
+    fn edits_that_change_nothing_are_not_recorded() {
+        let mut nodes = sample();
+        let ((), edit) = Edit::record(&mut nodes, |n| n.rename(1, "a").unwrap());
+        assert_eq!(edit, None);
+        let (result, edit) = Edit::record(&mut nodes, |n| n.move_node(1, Some(7)));
+        assert!(result.is_ok());
+        assert!(edit.is_some());
+    }
+
+    #[test]
+    fn recording_clears_redo_and_respects_the_limit() {
+        let mut nodes = sample();
+        let mut history = History::new(2);
+        for name in ["p", "q", "r"] {
+            edit(&mut history, &mut nodes, |n| n.rename(1, name).unwrap());
+        }
+        assert!(history.undo(&mut nodes));
+        assert!(history.undo(&mut nodes));
+        assert!(!history.undo(&mut nodes));
+        assert_eq!(nodes.get(1).unwrap().name, "p");
+
+        edit(&mut history, &mut nodes, |n| n.rename(1, "s").unwrap());
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn undo_leaves_loaded_children_loaded() {
+        let mut nodes: NodeList = NodeList::new(vec![Node::new(1, None, "a").with_lazy_children()]);
+        let mut history = History::default();
+        edit(&mut history, &mut nodes, |n| n.rename(1, "b").unwrap());
+        // Loading goes around the history, as the tree view does.
+        nodes
+            .merge_children(1, vec![Node::new(2, Some(1), "c")])
+            .unwrap();
+
+        assert!(history.undo(&mut nodes));
+        assert_eq!(nodes.get(1).unwrap().name, "a");
+        assert!(!nodes.get(1).unwrap().lazy);
+        assert_eq!(names(&nodes, Some(1)), ["c"]);
+        assert!(history.redo(&mut nodes));
+        assert!(!nodes.get(1).unwrap().lazy);
+    }
+}
@@ -0,0 +1,180 @@
+//! Keyboard navigation following the WAI-ARIA tree view pattern
+//! (https://www.w3.org/WAI/ARIA/apg/patterns/treeview/).
+
+use std::collections::HashSet;
+
+use crate::model::{NodeId, NodeList};
+
+/// What a key press on the focused row asks the tree to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction<I = i32> {
+    Focus(I),
+    Expand(I),
+    Collapse(I),
+}
+
+/// Maps a navigation key (`KeyboardEvent.key`) pressed on `focused` to an action.
+/// `shown` limits the rows as in [`NodeList::visible_rows`].
+pub fn navigate<T: Clone, I: NodeId>(
+    nodes: &NodeList<T, I>,
+    expanded: &HashSet<I>,
+    shown: Option<&HashSet<I>>,
+    focused: I,
+    key: &str,
+) -> Option<KeyAction<I>> {
+    let visible = nodes.visible_ids(expanded, shown);
+    let index = visible.iter().position(|&id| id == focused)?;
+    let has_children = nodes.has_children(focused);
+    let is_expanded = expanded.contains(&focused);
+
+    match key {
+        "ArrowDown" => visible.get(index + 1).copied().map(KeyAction::Focus),
+        "ArrowUp" => index.checked_sub(1).map(|i| KeyAction::Focus(visible[i])),
+        "ArrowRight" if has_children && !is_expanded => Some(KeyAction::Expand(focused)),
+        // Expanded: the first child is the next row, unless the children are still
+        // loading or all filtered out by a search.
+        "ArrowRight" if has_children => visible
+            .get(index + 1)
+            .filter(|&&next| {
+                nodes
+                    .get(next)
+                    .is_some_and(|n| n.parent_id == Some(focused))
+            })
+            .copied()
+            .map(KeyAction::Focus),
+        "ArrowLeft" if has_children && is_expanded => Some(KeyAction::Collapse(focused)),
+        "ArrowLeft" => nodes.get(focused)?.parent_id.map(KeyAction::Focus),
+        "Home" => visible.first().copied().map(KeyAction::Focus),
+        "End" => visible.last().copied().map(KeyAction::Focus),
+        _ => None,
+    }
+}
+
+/// The visible row whose name starts with the typed `prefix`, case-insensitively.
+///
+/// A single character (or the same one typed repeatedly) cycles through matches
+/// after `focused`; a longer prefix keeps `focused` if it still matches.
+pub fn type_ahead<T: Clone, I: NodeId>(
+    nodes: &NodeList<T, I>,
+    expanded: &HashSet<I>,
+    shown: Option<&HashSet<I>>,
+    focused: I,
+    prefix: &str,
+) -> Option<I> {
+    let prefix = prefix.to_lowercase();
+    let mut chars = prefix.chars();
+    let first = chars.next()?;
+    let (prefix, skip_current) = if chars.all(|c| c == first) {
+        (first.to_string(), true)
+    } else {
+        (prefix, false)
+    };
+
+    let visible = nodes.visible_ids(expanded, shown);
+    let start = visible.iter().position(|&id| id == focused).map_or(0, |i| {
+        if skip_current {
+            i + 1
+        } else {
+            i
+        }
+    });
+    visible
+        .iter()
+        .cycle()
+        .skip(start)
+        .take(visible.len())
+        .copied()
+        .find(|&id| {
+            nodes
+                .get(id)
+                .is_some_and(|n| n.name.to_lowercase().starts_with(&prefix))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Node;
+    use crate::testing::sample;
+
+    fn press(nodes: &NodeList, expanded: &[i32], focused: i32, key: &str) -> Option<KeyAction> {
+        let expanded: HashSet<i32> = expanded.iter().copied().collect();
+        navigate(nodes, &expanded, None, focused, key)
+    }
+
+    #[test]
+    fn arrows_move_through_visible_rows() {
+        let nodes = sample();
+        assert_eq!(
+            press(&nodes, &[1], 1, "ArrowDown"),
+            Some(KeyAction::Focus(2))
+        );
+        assert_eq!(press(&nodes, &[1], 2, "ArrowUp"), Some(KeyAction::Focus(1)));
+        assert_eq!(press(&nodes, &[1], 1, "ArrowUp"), None);
+        assert_eq!(
+            press(&nodes, &[], 1, "ArrowDown"),
+            Some(KeyAction::Focus(7))
+        );
+        assert_eq!(press(&nodes, &[1, 2], 1, "End"), Some(KeyAction::Focus(7)));
+        assert_eq!(press(&nodes, &[1, 2], 7, "Home"), Some(KeyAction::Focus(1)));
+    }
+
+    #[test]
+    fn right_and_left_open_close_and_climb() {
+        let nodes = sample();
+        assert_eq!(
+            press(&nodes, &[], 1, "ArrowRight"),
+            Some(KeyAction::Expand(1))
+        );
+        assert_eq!(
+            press(&nodes, &[1], 1, "ArrowRight"),
+            Some(KeyAction::Focus(2))
+        );
+        assert_eq!(press(&nodes, &[1], 7, "ArrowRight"), None);
+        assert_eq!(
+            press(&nodes, &[1], 1, "ArrowLeft"),
+            Some(KeyAction::Collapse(1))
+        );
+        assert_eq!(
+            press(&nodes, &[1], 2, "ArrowLeft"),
+            Some(KeyAction::Focus(1))
+        );
+        assert_eq!(press(&nodes, &[1], 1, "Tab"), None);
+    }
+
+    #[test]
+    fn right_stays_put_when_no_child_is_shown() {
+        // 2 is expanded but its children are filtered out.
+        let nodes = sample();
+        let expanded = HashSet::from([1, 2]);
+        let shown = HashSet::from([1, 2, 3, 6, 7]);
+        assert_eq!(
+            navigate(&nodes, &expanded, Some(&shown), 2, "ArrowRight"),
+            None
+        );
+
+        // 8 is expanded but its children are still loading.
+        let mut nodes = sample();
+        nodes
+            .insert(Node::new(8, Some(1), "h").with_lazy_children())
+            .unwrap();
+        nodes.insert(Node::new(9, Some(1), "i")).unwrap();
+        let expanded = HashSet::from([1, 8]);
+        assert!(nodes.has_children(8));
+        assert_eq!(navigate(&nodes, &expanded, None, 8, "ArrowRight"), None);
+    }
+
+    #[test]
+    fn type_ahead_cycles_through_matches() {
+        let mut nodes = sample();
+        nodes.rename(3, "de").unwrap();
+        let expanded = HashSet::from([1, 2]);
+        // Visible rows: a, b, d, e, de, g.
+        assert_eq!(type_ahead(&nodes, &expanded, None, 1, "d"), Some(4));
+        assert_eq!(type_ahead(&nodes, &expanded, None, 4, "d"), Some(3));
+        assert_eq!(type_ahead(&nodes, &expanded, None, 3, "d"), Some(4));
+        assert_eq!(type_ahead(&nodes, &expanded, None, 1, "de"), Some(3));
+        assert_eq!(type_ahead(&nodes, &expanded, None, 3, "DE"), Some(3));
+        assert_eq!(type_ahead(&nodes, &expanded, None, 1, "x"), None);
+    }
+}
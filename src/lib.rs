@@ -0,0 +1,11 @@
+pub mod components;
+pub mod history;
+pub mod keyboard;
+pub mod model;
+pub mod nested;
+pub mod persist;
+pub mod search;
+pub mod traverse;
+
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,1215 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A node id. Ids are also written to drag-and-drop data, hence `Display` and `FromStr`.
+pub trait NodeId: Copy + Eq + Hash + Debug + Display + FromStr + 'static {
+    /// `count` distinct ids that aren't among `existing`, or `None` if there
+    /// aren't that many left.
+    fn fresh(existing: impl Iterator<Item = Self>, count: usize) -> Option<Vec<Self>>;
+}
+
+impl NodeId for i32 {
+    /// Counts up from one more than the highest id in use, up to `i32::MAX`.
+    fn fresh(existing: impl Iterator<Item = i32>, count: usize) -> Option<Vec<i32>> {
+        let start = existing.max().map_or(Some(1), |id| id.checked_add(1))?;
+        (0..i32::try_from(count).ok()?)
+            .map(|i| start.checked_add(i))
+            .collect()
+    }
+}
+
+impl NodeId for Uuid {
+    /// A random v4 UUID, so ids made in different tabs or devices don't collide.
+    fn fresh(_existing: impl Iterator<Item = Uuid>, count: usize) -> Option<Vec<Uuid>> {
+        Some((0..count).map(|_| Uuid::new_v4()).collect())
+    }
+}
+
+/// A tree node. `T` is the caller's payload, stored and persisted alongside the node,
+/// and `I` the id type: hand-picked `i32`s, or [`Uuid`]s for trees edited in several
+/// places at once.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Node<T = (), I = i32> {
+    pub id: I,
+    pub parent_id: Option<I>,
+    pub name: String,
+    /// Sort key among siblings. Ranks are spaced out so a move usually only
+    /// rewrites the moved node. Ties, e.g. from nodes made with `new`, are
+    /// broken in the order the nodes were added and then spread out.
+    #[serde(default)]
+    pub rank: i64,
+    /// Has children that aren't in the list yet; they are fetched on first expand.
+    #[serde(default)]
+    pub lazy: bool,
+    pub data: T,
+}
+
+impl<T: Default, I: NodeId> Node<T, I> {
+    pub fn new(id: I, parent_id: Option<I>, name: &str) -> Node<T, I> {
+        Node::with_data(id, parent_id, name, T::default())
+    }
+}
+
+impl<T, I: NodeId> Node<T, I> {
+    pub fn with_data(id: I, parent_id: Option<I>, name: &str, data: T) -> Node<T, I> {
+        Node {
+            id,
+            parent_id,
+            name: name.to_owned(),
+            rank: 0,
+            lazy: false,
+            data,
+        }
+    }
+
+    /// Marks the node as having children to load on demand.
+    pub fn with_lazy_children(mut self) -> Node<T, I> {
+        self.lazy = true;
+        self
+    }
+}
+
+/// All nodes of a tree, with indexes from id to node and from parent to children
+/// that are kept up to date by every change. With `n` nodes, `k` children of the
+/// parent involved and `d` the depth of a node:
+///
+/// | Operation | Cost |
+/// |---|---|
+/// | `get`, `contains`, `has_children` | O(1) |
+/// | `children`, `child_ids`, `siblings`, `sibling_position`, `get_root_nodes` | O(k) |
+/// | `insert`, `update`, `rename`, moves and drops | O(k + d) |
+/// | `ancestors`, `path`, `depth`, `lowest_common_ancestor` | O(d) |
+/// | `is_ancestor`, `can_move`, `can_drop` | O(d) |
+/// | `visible_rows`, `visible_ids` | O(rows shown) |
+/// | `descendants`, `subtree_size`, `subtree_ids` | O(subtree size) |
+/// | `delete`, `remove_nodes` | O(k) per removed node |
+/// | `add_child`, `add_sibling` | O(k + d), plus O(n) for `i32` ids |
+/// | `duplicate` | O(k + d) per copied node, plus O(n) for `i32` ids |
+/// | `next_id` for `i32` ids | O(n) |
+#[derive(Debug, Clone)]
+pub struct NodeList<T = (), I = i32> {
+    list: Vec<Node<T, I>>,
+    /// Where each node is in `list`.
+    positions: HashMap<I, usize>,
+    /// Child ids of each parent (`None` for the roots), in rank order.
+    children: HashMap<Option<I>, Vec<I>>,
+    /// The changes made so far, while [`record`](NodeList::record) runs.
+    journal: Option<Vec<Change<T, I>>>,
+}
+
+impl<T, I> Default for NodeList<T, I> {
+    fn default() -> Self {
+        NodeList {
+            list: Vec::new(),
+            positions: HashMap::new(),
+            children: HashMap::new(),
+            journal: None,
+        }
+    }
+}
+
+/// One primitive change to a [`NodeList`], holding what it overwrote so it can
+/// be reverted. Every edit is a sequence of these.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Change<T, I> {
+    /// The node `id` was added, replaced or removed; `old` is the version before.
+    Put { id: I, old: Option<Node<T, I>> },
+    /// The node `id` was re-ranked in place from `old`.
+    Rank { id: I, old: i64 },
+}
+
+impl<T, I: Copy> Change<T, I> {
+    /// The node changed.
+    pub(crate) fn id(&self) -> I {
+        match self {
+            Change::Put { id, .. } | Change::Rank { id, .. } => *id,
+        }
+    }
+}
+
+impl<T, I: NodeId> From<Vec<Node<T, I>>> for NodeList<T, I> {
+    fn from(list: Vec<Node<T, I>>) -> Self {
+        NodeList::new(list)
+    }
+}
+
+impl<T, I: NodeId> NodeList<T, I> {
+    /// Indexes `list`. O(n log n). The nodes are trusted to form a tree: use
+    /// [`try_new`](NodeList::try_new) for nodes loaded from storage or the network.
+    pub fn new(list: Vec<Node<T, I>>) -> NodeList<T, I> {
+        let mut nodes = NodeList {
+            list,
+            ..NodeList::default()
+        };
+        nodes.reindex();
+        nodes
+    }
+
+    /// Like [`new`](NodeList::new), but fails if the nodes don't form a tree.
+    pub fn try_new(list: Vec<Node<T, I>>) -> Result<NodeList<T, I>, Vec<TreeError<I>>> {
+        let nodes = NodeList::new(list);
+        nodes.validate()?;
+        Ok(nodes)
+    }
+
+    /// All nodes, in no particular order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Node<T, I>> {
+        self.list.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn get(&self, id: I) -> Option<&Node<T, I>> {
+        self.positions.get(&id).map(|&i| &self.list[i])
+    }
+
+    pub fn contains(&self, id: I) -> bool {
+        self.positions.contains_key(&id)
+    }
+
+    /// The children of `parent_id` (the roots when `None`) in rank order.
+    pub fn children(&self, parent_id: Option<I>) -> impl DoubleEndedIterator<Item = &Node<T, I>> {
+        self.children
+            .get(&parent_id)
+            .into_iter()
+            .flatten()
+            .map(|id| &self.list[self.positions[id]])
+    }
+
+    /// Ids of the children of `parent_id` (the roots when `None`) in rank order.
+    pub fn child_ids(&self, parent_id: Option<I>) -> Vec<I> {
+        self.children.get(&parent_id).cloned().unwrap_or_default()
+    }
+
+    /// Whether `id` has children, loaded or still [`lazy`](Node::lazy).
+    pub fn has_children(&self, id: I) -> bool {
+        self.get(id).is_some_and(|n| n.lazy) || self.children.contains_key(&Some(id))
+    }
+
+    /// The 1-based position of `id` among its siblings, and the number of siblings.
+    pub fn sibling_position(&self, id: I) -> Option<(usize, usize)> {
+        let siblings = self.children.get(&self.get(id)?.parent_id)?;
+        let index = siblings.iter().position(|&s| s == id)?;
+        Some((index + 1, siblings.len()))
+    }
+
+    /// Whether `id` can be put under `parent_id` without creating a cycle.
+    pub fn can_move(&self, id: I, parent_id: Option<I>) -> bool {
+        !parent_id.is_some_and(|p| p == id || self.is_ancestor(id, p))
+    }
+
+    /// Whether `ancestor` is on the `parent_id` chain above `id`.
+    pub fn is_ancestor(&self, ancestor: I, id: I) -> bool {
+        self.ancestors(id).any(|n| n.id == ancestor)
+    }
+
+    /// A fresh id for a new node.
+    pub fn next_id(&self) -> Result<I, EditError<I>> {
+        self.fresh_ids(1).map(|ids| ids[0])
+    }
+
+    /// `count` fresh ids for new nodes.
+    fn fresh_ids(&self, count: usize) -> Result<Vec<I>, EditError<I>> {
+        I::fresh(self.list.iter().map(|n| n.id), count).ok_or(EditError::OutOfIds)
+    }
+
+    /// Checks that the nodes form a tree: ids are unique, every `parent_id` is the
+    /// id of a node in the list, and no node is its own ancestor. Reports every
+    /// problem found, not just the first.
+    pub fn validate(&self) -> Result<(), Vec<TreeError<I>>> {
+        let mut errors = Vec::new();
+        let mut seen = HashSet::new();
+        let mut duplicates = HashSet::new();
+        for node in &self.list {
+            if !seen.insert(node.id) && duplicates.insert(node.id) {
+                errors.push(TreeError::DuplicateId(node.id));
+            }
+        }
+        for node in &self.list {
+            if let Some(parent_id) = node.parent_id.filter(|&p| !self.contains(p)) {
+                errors.push(TreeError::Orphan {
+                    id: node.id,
+                    parent_id,
+                });
+            }
+        }
+        // Walk up from every node until reaching a root, a missing parent, a node
+        // already walked from, or a node on the current walk, which closes a cycle.
+        let mut walked = HashSet::new();
+        for node in &self.list {
+            let mut path: HashMap<I, usize> = HashMap::new();
+            let mut order = Vec::new();
+            let mut current = Some(node.id);
+            while let Some(id) = current.filter(|id| !walked.contains(id)) {
+                if let Some(&start) = path.get(&id) {
+                    errors.push(match order[start..] {
+                        [id] => TreeError::SelfParent(id),
+                        ref cycle => TreeError::Cycle(cycle.to_vec()),
+                    });
+                    break;
+                }
+                path.insert(id, order.len());
+                order.push(id);
+                current = self.get(id).and_then(|n| n.parent_id);
+            }
+            walked.extend(order);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn reindex(&mut self) {
+        self.positions = self
+            .list
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.id, i))
+            .collect();
+        let mut children: HashMap<Option<I>, Vec<I>> = HashMap::new();
+        for node in &self.list {
+            children.entry(node.parent_id).or_default().push(node.id);
+        }
+        for siblings in children.values_mut() {
+            siblings.sort_by_key(|id| self.list[self.positions[id]].rank);
+        }
+        let tied: Vec<Option<I>> = children
+            .iter()
+            .filter(|(_, siblings)| {
+                siblings.windows(2).any(|pair| {
+                    self.list[self.positions[&pair[0]]].rank
+                        == self.list[self.positions[&pair[1]]].rank
+                })
+            })
+            .map(|(&parent_id, _)| parent_id)
+            .collect();
+        self.children = children;
+        for parent_id in tied {
+            self.spread(parent_id);
+        }
+    }
+
+    /// Renumbers the ranks of the children of `parent_id`, keeping their order, so
+    /// they are evenly spaced. Siblings never share a rank, so a node that is taken
+    /// out and put back (e.g. by an undo) returns to the same place.
+    fn spread(&mut self, parent_id: Option<I>) {
+        for (i, sibling) in self.child_ids(parent_id).into_iter().enumerate() {
+            self.relabel(sibling, i as i64 * RANK_STEP);
+        }
+    }
+
+    /// Whether `id` ranks strictly between its neighbours in the child index.
+    fn in_rank_order(&self, id: I) -> bool {
+        let parent_id = self.list[self.positions[&id]].parent_id;
+        let Some(siblings) = self.children.get(&parent_id) else {
+            return false;
+        };
+        let Some(index) = siblings.iter().position(|&s| s == id) else {
+            return false;
+        };
+        let rank_at = |i: usize| self.list[self.positions[&siblings[i]]].rank;
+        let rank = rank_at(index);
+        index
+            .checked_sub(1)
+            .is_none_or(|before| rank_at(before) < rank)
+            && (index + 1 >= siblings.len() || rank < rank_at(index + 1))
+    }
+
+    /// Sets the rank of `id` without moving it in the child index, so the caller
+    /// must keep the siblings' order.
+    fn relabel(&mut self, id: I, rank: i64) {
+        let node = &mut self.list[self.positions[&id]];
+        if node.rank == rank {
+            return;
+        }
+        let old = std::mem::replace(&mut node.rank, rank);
+        if let Some(journal) = &mut self.journal {
+            journal.push(Change::Rank { id, old });
+        }
+    }
+
+    /// Takes `id` out of its parent's child index.
+    fn unlink(&mut self, id: I) {
+        let parent_id = self.list[self.positions[&id]].parent_id;
+        if let Some(siblings) = self.children.get_mut(&parent_id) {
+            siblings.retain(|&s| s != id);
+            if siblings.is_empty() {
+                self.children.remove(&parent_id);
+            }
+        }
+    }
+
+    /// Puts `id` into its parent's child index, after the siblings of equal or lower
+    /// rank. A tie with the sibling before it spreads the ranks out if `spread_ties`.
+    fn link(&mut self, id: I, spread_ties: bool) {
+        let node = &self.list[self.positions[&id]];
+        let (parent_id, rank) = (node.parent_id, node.rank);
+        let (list, positions) = (&self.list, &self.positions);
+        let siblings = self.children.entry(parent_id).or_default();
+        let index = siblings.partition_point(|s| list[positions[s]].rank <= rank);
+        siblings.insert(index, id);
+        let tied = index
+            .checked_sub(1)
+            .is_some_and(|before| list[positions[&siblings[before]]].rank == rank);
+        if tied && spread_ties {
+            self.spread(parent_id);
+        }
+    }
+}
+
+impl<T: Clone, I: NodeId> NodeList<T, I> {
+    /// Adds `node`, replacing the node with the same id if there is one. The
+    /// parent doesn't have to be in the list yet, but `node` can't end up as
+    /// its own ancestor.
+    pub fn insert(&mut self, node: Node<T, I>) -> Result<(), EditError<I>> {
+        let id = node.id;
+        if let Some(parent_id) = node.parent_id.filter(|&p| !self.can_move(id, Some(p))) {
+            return Err(EditError::WouldCycle { id, parent_id });
+        }
+        self.put(id, Some(node), true);
+        Ok(())
+    }
+
+    /// Removes the nodes in `ids` (not their descendants), returning them.
+    pub fn remove_nodes(&mut self, ids: &HashSet<I>) -> Vec<Node<T, I>> {
+        ids.iter()
+            .filter_map(|&id| self.put(id, None, true))
+            .collect()
+    }
+
+    /// Runs `f`, returning its result along with the changes it made, which
+    /// [`revert`](NodeList::revert) can undo.
+    pub(crate) fn record<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> (R, Vec<Change<T, I>>) {
+        let outer = self.journal.replace(Vec::new());
+        let result = f(self);
+        let changes = std::mem::replace(&mut self.journal, outer).unwrap_or_default();
+        if let Some(outer) = &mut self.journal {
+            outer.extend(changes.iter().cloned());
+        }
+        (result, changes)
+    }
+
+    /// Undoes `changes`, last first, returning the changes that redo them.
+    pub(crate) fn revert(&mut self, changes: Vec<Change<T, I>>) -> Vec<Change<T, I>> {
+        self.record(|nodes| {
+            for change in changes.into_iter().rev() {
+                match change {
+                    // The old versions fit back exactly, so ties need no spreading.
+                    Change::Put { id, mut old } => {
+                        // Loading a lazy node's children isn't an edit, so an undo
+                        // mustn't mark it as unloaded again.
+                        if let (Some(old), Some(current)) = (&mut old, nodes.get(id)) {
+                            old.lazy = current.lazy;
+                        }
+                        nodes.put(id, old, false);
+                    }
+                    Change::Rank { id, old } => {
+                        if nodes.contains(id) {
+                            nodes.unlink(id);
+                            nodes.relabel(id, old);
+                            nodes.link(id, false);
+                        }
+                    }
+                }
+            }
+        })
+        .1
+    }
+
+    /// Replaces the node `id` with `node`, or removes it when `None`, keeping the
+    /// indexes up to date. Returns the node it replaced.
+    fn put(&mut self, id: I, node: Option<Node<T, I>>, spread_ties: bool) -> Option<Node<T, I>> {
+        let old = match (self.positions.get(&id).copied(), node) {
+            (None, None) => return None,
+            // Same parent and rank, and the siblings around it weren't renumbered past
+            // that rank: the node keeps its slot among them.
+            (Some(i), Some(node))
+                if self.list[i].parent_id == node.parent_id
+                    && self.list[i].rank == node.rank
+                    && self.in_rank_order(id) =>
+            {
+                Some(std::mem::replace(&mut self.list[i], node))
+            }
+            (Some(i), node) => {
+                self.unlink(id);
+                self.positions.remove(&id);
+                let old = self.list.swap_remove(i);
+                if let Some(moved) = self.list.get(i) {
+                    self.positions.insert(moved.id, i);
+                }
+                if let Some(node) = node {
+                    self.positions.insert(id, self.list.len());
+                    self.list.push(node);
+                    self.link(id, spread_ties);
+                }
+                Some(old)
+            }
+            (None, Some(node)) => {
+                self.positions.insert(id, self.list.len());
+                self.list.push(node);
+                self.link(id, spread_ties);
+                None
+            }
+        };
+        if let Some(journal) = &mut self.journal {
+            journal.push(Change::Put {
+                id,
+                old: old.clone(),
+            });
+        }
+        old
+    }
+
+    /// Changes the node `id`. Nothing changes if `f` alters the id or makes the
+    /// node its own ancestor.
+    pub fn update(&mut self, id: I, f: impl FnOnce(&mut Node<T, I>)) -> Result<(), EditError<I>> {
+        let mut node = self.get(id).ok_or(EditError::NotFound(id))?.clone();
+        f(&mut node);
+        if node.id != id {
+            return Err(EditError::IdChanged {
+                id,
+                new_id: node.id,
+            });
+        }
+        self.insert(node)
+    }
+
+    pub fn rename(&mut self, id: I, name: &str) -> Result<(), EditError<I>> {
+        self.update(id, |node| node.name = name.to_owned())
+    }
+
+    pub fn get_root_nodes(&self) -> Vec<Node<T, I>> {
+        self.children(None).cloned().collect()
+    }
+
+    /// Makes `id` the last child of `parent_id` (or the last root when `None`),
+    /// carrying its whole subtree along.
+    pub fn move_node(&mut self, id: I, parent_id: Option<I>) -> Result<(), EditError<I>> {
+        self.relocate(id, parent_id, |siblings| Ok(siblings.len()))
+    }
+
+    /// Makes `id` the sibling right before `target`.
+    pub fn move_before(&mut self, id: I, target: I) -> Result<(), EditError<I>> {
+        if id == target {
+            return Err(EditError::OntoItself(id));
+        }
+        let parent_id = self
+            .get(target)
+            .ok_or(EditError::NotFound(target))?
+            .parent_id;
+        self.relocate(id, parent_id, |siblings| {
+            siblings
+                .iter()
+                .position(|&s| s == target)
+                .ok_or(EditError::NotFound(target))
+        })
+    }
+
+    /// Makes `id` the sibling right after `target`.
+    pub fn move_after(&mut self, id: I, target: I) -> Result<(), EditError<I>> {
+        if id == target {
+            return Err(EditError::OntoItself(id));
+        }
+        let parent_id = self
+            .get(target)
+            .ok_or(EditError::NotFound(target))?
+            .parent_id;
+        self.relocate(id, parent_id, |siblings| {
+            siblings
+                .iter()
+                .position(|&s| s == target)
+                .map(|i| i + 1)
+                .ok_or(EditError::NotFound(target))
+        })
+    }
+
+    pub fn drop_node(
+        &mut self,
+        id: I,
+        target: I,
+        position: DropPosition,
+    ) -> Result<(), EditError<I>> {
+        match position {
+            DropPosition::Before => self.move_before(id, target),
+            DropPosition::Inside => self.move_node(id, Some(target)),
+            DropPosition::After => self.move_after(id, target),
+        }
+    }
+
+    /// Drops several nodes on `target` at once, keeping them in the order given.
+    /// Nothing moves unless every node can be dropped there.
+    pub fn drop_nodes(
+        &mut self,
+        ids: &[I],
+        target: I,
+        position: DropPosition,
+    ) -> Result<(), EditError<I>> {
+        for &id in ids {
+            self.check_drop(id, target, position)?;
+        }
+        match position {
+            // Each node goes right after `target`, so insert the last one first.
+            DropPosition::After => ids
+                .iter()
+                .rev()
+                .try_for_each(|&id| self.drop_node(id, target, position)),
+            DropPosition::Before | DropPosition::Inside => ids
+                .iter()
+                .try_for_each(|&id| self.drop_node(id, target, position)),
+        }
+    }
+
+    pub fn can_drop_all(&self, ids: &[I], target: I, position: DropPosition) -> bool {
+        ids.iter().all(|&id| self.can_drop(id, target, position))
+    }
+
+    /// Adds the loaded children of a lazy node, replacing any already in the list,
+    /// and marks `parent_id` as loaded. Changes nothing if any child can't be added,
+    /// so the node stays lazy and can be loaded again.
+    pub fn merge_children(
+        &mut self,
+        parent_id: I,
+        children: Vec<Node<T, I>>,
+    ) -> Result<(), EditError<I>> {
+        if !self.contains(parent_id) {
+            return Err(EditError::NotFound(parent_id));
+        }
+        // Check the tree as it will be once every child is in, since the children
+        // can be each other's parents.
+        let loaded: HashMap<I, Option<I>> = children.iter().map(|c| (c.id, c.parent_id)).collect();
+        let parent_of = |id: I| match loaded.get(&id) {
+            Some(&parent_id) => parent_id,
+            None => self.get(id).and_then(|n| n.parent_id),
+        };
+        for child in &children {
+            let mut above = child.parent_id;
+            for _ in 0..=self.len() + loaded.len() {
+                let Some(ancestor) = above else {
+                    break;
+                };
+                if ancestor == child.id {
+                    return Err(EditError::WouldCycle {
+                        id: child.id,
+                        parent_id: child.parent_id.unwrap_or(ancestor),
+                    });
+                }
+                above = parent_of(ancestor);
+            }
+        }
+        for child in children {
+            self.put(child.id, Some(child), true);
+        }
+        self.update(parent_id, |parent| parent.lazy = false)
+    }
+
+    /// Whether `drop_node` would accept this drop.
+    pub fn can_drop(&self, id: I, target: I, position: DropPosition) -> bool {
+        self.check_drop(id, target, position).is_ok()
+    }
+
+    /// Why `drop_node` would refuse this drop, if it would: one of the nodes is
+    /// missing, or the new parent is `id` or one of its descendants.
+    pub fn check_drop(&self, id: I, target: I, position: DropPosition) -> Result<(), EditError<I>> {
+        if id == target {
+            return Err(EditError::OntoItself(id));
+        }
+        let target_node = self.get(target).ok_or(EditError::NotFound(target))?;
+        let parent_id = match position {
+            DropPosition::Inside => Some(target),
+            DropPosition::Before | DropPosition::After => target_node.parent_id,
+        };
+        self.check_move(id, parent_id)
+    }
+
+    /// Why `id` can't be put under `parent_id`, if it can't.
+    fn check_move(&self, id: I, parent_id: Option<I>) -> Result<(), EditError<I>> {
+        if !self.contains(id) {
+            return Err(EditError::NotFound(id));
+        }
+        match parent_id {
+            Some(p) if !self.contains(p) => Err(EditError::NotFound(p)),
+            Some(p) if !self.can_move(id, parent_id) => {
+                Err(EditError::WouldCycle { id, parent_id: p })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Ids of the rows shown when only the `expanded` nodes are open, top to bottom.
+    /// See [`visible_rows`](NodeList::visible_rows) for `shown`.
+    pub fn visible_ids(&self, expanded: &HashSet<I>, shown: Option<&HashSet<I>>) -> Vec<I> {
+        self.visible_rows(expanded, shown)
+            .into_iter()
+            .map(|row| row.node.id)
+            .collect()
+    }
+
+    /// The rows shown when only the `expanded` nodes are open, top to bottom,
+    /// i.e. the tree flattened for rendering. When `shown` is given, as while
+    /// searching, other nodes are left out along with their subtrees.
+    pub fn visible_rows(
+        &self,
+        expanded: &HashSet<I>,
+        shown: Option<&HashSet<I>>,
+    ) -> Vec<VisibleRow<'_, T, I>> {
+        let is_shown = |node: &&Node<T, I>| shown.is_none_or(|shown| shown.contains(&node.id));
+        let mut visible = Vec::new();
+        let mut stack: Vec<(&Node<T, I>, usize)> = self
+            .children(None)
+            .rev()
+            .filter(is_shown)
+            .map(|n| (n, 0))
+            .collect();
+        while let Some((node, depth)) = stack.pop() {
+            visible.push(VisibleRow { node, depth });
+            if expanded.contains(&node.id) {
+                stack.extend(
+                    self.children(Some(node.id))
+                        .rev()
+                        .filter(is_shown)
+                        .map(|n| (n, depth + 1)),
+                );
+            }
+        }
+        visible
+    }
+
+    /// Adds a node as the last child of `parent_id` (or the last root when `None`)
+    /// and returns its id.
+    pub fn add_child(
+        &mut self,
+        parent_id: Option<I>,
+        name: &str,
+        data: T,
+    ) -> Result<I, EditError<I>> {
+        if let Some(p) = parent_id.filter(|&p| !self.contains(p)) {
+            return Err(EditError::NotFound(p));
+        }
+        let id = self.next_id()?;
+        self.insert(Node::with_data(id, parent_id, name, data))?;
+        self.move_node(id, parent_id)?;
+        Ok(id)
+    }
+
+    /// Adds a node right after `target`, under the same parent.
+    pub fn add_sibling(&mut self, target: I, name: &str, data: T) -> Result<I, EditError<I>> {
+        let parent_id = self
+            .get(target)
+            .ok_or(EditError::NotFound(target))?
+            .parent_id;
+        let id = self.next_id()?;
+        self.insert(Node::with_data(id, parent_id, name, data))?;
+        self.move_after(id, target)?;
+        Ok(id)
+    }
+
+    /// Removes `id` and its whole subtree, returning the removed nodes.
+    pub fn delete(&mut self, id: I) -> Result<Vec<Node<T, I>>, EditError<I>> {
+        if !self.contains(id) {
+            return Err(EditError::NotFound(id));
+        }
+        let ids: HashSet<I> = self.subtree_ids(id).into_iter().collect();
+        Ok(self.remove_nodes(&ids))
+    }
+
+    /// Deep-copies `id` and its subtree under fresh ids, placing the copy right
+    /// after the original under a name its siblings don't use, e.g. `Sales 2`.
+    /// Returns the id of the copy.
+    pub fn duplicate(&mut self, id: I) -> Result<I, EditError<I>> {
+        if !self.contains(id) {
+            return Err(EditError::NotFound(id));
+        }
+        let ids = self.subtree_ids(id);
+        let fresh: HashMap<I, I> = ids
+            .iter()
+            .copied()
+            .zip(self.fresh_ids(ids.len())?)
+            .collect();
+        let copies: Vec<Node<T, I>> = ids
+            .iter()
+            .filter_map(|&old| self.get(old))
+            .map(|node| {
+                let mut copy = node.clone();
+                copy.id = fresh[&node.id];
+                // The copied root keeps the original's parent.
+                copy.parent_id = copy.parent_id.map(|p| fresh.get(&p).copied().unwrap_or(p));
+                copy
+            })
+            .collect();
+        let copy = fresh[&id];
+        let original = &self.list[self.positions[&id]];
+        let name = self.unique_name(original.parent_id, &original.name);
+        for mut node in copies {
+            if node.id == copy {
+                node.name = name.clone();
+            }
+            self.insert(node)?;
+        }
+        self.move_after(copy, id)?;
+        Ok(copy)
+    }
+
+    /// `id` followed by all its descendants, depth first; empty if `id` doesn't exist.
+    pub fn subtree_ids(&self, id: I) -> Vec<I> {
+        self.get(id)
+            .into_iter()
+            .chain(self.descendants(id))
+            .map(|n| n.id)
+            .collect()
+    }
+
+    /// `base`, or `base 2`, `base 3`, ... if a child of `parent_id` already has that name.
+    pub fn unique_name(&self, parent_id: Option<I>, base: &str) -> String {
+        let taken: HashSet<&str> = self.children(parent_id).map(|n| n.name.as_str()).collect();
+        std::iter::once(base.to_owned())
+            .chain((2..).map(|i| format!("{} {}", base, i)))
+            .find(|name| !taken.contains(name.as_str()))
+            .unwrap()
+    }
+
+    /// The default rename check: names must not be blank or repeat a sibling's name.
+    pub fn validate_name(&self, id: I, name: &str) -> Result<(), String> {
+        if name.trim().is_empty() {
+            return Err("Name can't be empty".to_owned());
+        }
+        let parent_id = self.get(id).and_then(|n| n.parent_id);
+        let taken = self
+            .children(parent_id)
+            .any(|n| n.id != id && n.name == name);
+        if taken {
+            return Err(format!("A sibling is already named \"{}\"", name));
+        }
+        Ok(())
+    }
+
+    /// The nodes of `ids` that have no ancestor in `ids`, in tree order. Moving
+    /// these moves the whole set, as descendants come along with their ancestors.
+    pub fn topmost(&self, ids: &HashSet<I>) -> Vec<I> {
+        let mut topmost = Vec::new();
+        let mut stack = self.child_ids(None);
+        stack.reverse();
+        while let Some(id) = stack.pop() {
+            if ids.contains(&id) {
+                topmost.push(id);
+            } else {
+                stack.extend(self.children(Some(id)).rev().map(|n| n.id));
+            }
+        }
+        topmost
+    }
+
+    /// Puts `id` under `parent_id` at the sibling index picked by `index`, which
+    /// sees the new siblings in order without `id` itself. Only the moved node is
+    /// re-ranked unless its neighbours leave no room, in which case the sibling
+    /// group is renumbered.
+    fn relocate(
+        &mut self,
+        id: I,
+        parent_id: Option<I>,
+        index: impl FnOnce(&[I]) -> Result<usize, EditError<I>>,
+    ) -> Result<(), EditError<I>> {
+        self.check_move(id, parent_id)?;
+        let mut siblings = self.child_ids(parent_id);
+        siblings.retain(|&s| s != id);
+        let index = index(&siblings)?;
+        let rank_of = |id: I| self.get(id).map(|n| n.rank);
+        let before = index.checked_sub(1).and_then(|i| rank_of(siblings[i]));
+        let after = siblings.get(index).and_then(|&s| rank_of(s));
+
+        match rank_between(before, after) {
+            Some(rank) => {
+                self.update(id, |node| {
+                    node.parent_id = parent_id;
+                    node.rank = rank;
+                })?;
+            }
+            // No room between the neighbours: renumber the siblings, leaving a gap at `index`.
+            None => {
+                for (i, &sibling) in siblings.iter().enumerate() {
+                    let slot = if i < index { i } else { i + 1 };
+                    self.relabel(sibling, slot as i64 * RANK_STEP);
+                }
+                let rank = index as i64 * RANK_STEP;
+                self.update(id, |node| {
+                    node.parent_id = parent_id;
+                    node.rank = rank;
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One row of the flattened tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisibleRow<'a, T = (), I = i32> {
+    pub node: &'a Node<T, I>,
+    /// 0 for root nodes.
+    pub depth: usize,
+}
+
+/// A way in which a list of nodes isn't a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeError<I = i32> {
+    /// More than one node has this id.
+    DuplicateId(I),
+    /// The node's `parent_id` is its own id.
+    SelfParent(I),
+    /// The node's `parent_id` isn't the id of any node.
+    Orphan { id: I, parent_id: I },
+    /// Nodes that are their own ancestors, each the child of the next.
+    Cycle(Vec<I>),
+}
+
+impl<I: Display> fmt::Display for TreeError<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::DuplicateId(id) => write!(f, "more than one node has id {}", id),
+            TreeError::SelfParent(id) => write!(f, "node {} is its own parent", id),
+            TreeError::Orphan { id, parent_id } => {
+                write!(
+                    f,
+                    "node {} has parent {}, which doesn't exist",
+                    id, parent_id
+                )
+            }
+            TreeError::Cycle(ids) => {
+                let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+                write!(f, "nodes {} are their own ancestors", ids.join(", "))
+            }
+        }
+    }
+}
+
+impl<I: Debug + Display> std::error::Error for TreeError<I> {}
+
+/// Why a change to a [`NodeList`] was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError<I = i32> {
+    /// There is no node with this id.
+    NotFound(I),
+    /// Putting `id` under `parent_id` would make it its own ancestor.
+    WouldCycle { id: I, parent_id: I },
+    /// A node can't be dropped on, or moved next to, itself.
+    OntoItself(I),
+    /// [`NodeList::update`] was asked to change a node's id.
+    IdChanged { id: I, new_id: I },
+    /// Every id is taken, so no node can be added.
+    OutOfIds,
+}
+
+impl<I: Display> fmt::Display for EditError<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::NotFound(id) => write!(f, "there is no node {}", id),
+            EditError::WouldCycle { id, parent_id } => write!(
+                f,
+                "node {} can't go under {}, which is inside it",
+                id, parent_id
+            ),
+            EditError::OntoItself(id) => write!(f, "node {} can't be moved onto itself", id),
+            EditError::IdChanged { id, new_id } => {
+                write!(f, "node {} can't change its id to {}", id, new_id)
+            }
+            EditError::OutOfIds => write!(f, "there are no ids left for a new node"),
+        }
+    }
+}
+
+impl<I: Debug + Display> std::error::Error for EditError<I> {}
+
+/// Where a dragged node lands relative to the row it is dropped on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropPosition {
+    Before,
+    Inside,
+    After,
+}
+
+impl DropPosition {
+    /// Picks the zone from the pointer's offset within a row of the given height:
+    /// the top quarter inserts before, the bottom quarter after, the rest nests inside.
+    pub fn from_offset(offset: f64, height: f64) -> DropPosition {
+        if offset < height / 4.0 {
+            DropPosition::Before
+        } else if offset > height * 3.0 / 4.0 {
+            DropPosition::After
+        } else {
+            DropPosition::Inside
+        }
+    }
+}
+
+const RANK_STEP: i64 = 1 << 16;
+
+/// A rank strictly between two neighbours, or `None` if they are adjacent.
+fn rank_between(before: Option<i64>, after: Option<i64>) -> Option<i64> {
+    match (before, after) {
+        (None, None) => Some(0),
+        (Some(before), None) => before.checked_add(RANK_STEP),
+        (None, Some(after)) => after.checked_sub(RANK_STEP),
+        (Some(before), Some(after)) => after
+            .checked_sub(before)
+            .filter(|gap| *gap > 1)
+            .map(|gap| before + gap / 2),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{names, roots, sample};
+
+    #[test]
+    fn add_child_appends_under_the_parent() {
+        let mut nodes = sample();
+        let id = nodes.add_child(Some(1), "h", ()).unwrap();
+        assert_eq!(id, 8);
+        assert_eq!(names(&nodes, Some(1)), ["b", "c", "h"]);
+        assert_eq!(
+            nodes.add_child(Some(9), "i", ()),
+            Err(EditError::NotFound(9))
+        );
+    }
+
+    #[test]
+    fn add_sibling_goes_right_after_the_target() {
+        let mut nodes = sample();
+        nodes.add_sibling(2, "h", ()).unwrap();
+        assert_eq!(names(&nodes, Some(1)), ["b", "h", "c"]);
+        nodes.add_sibling(1, "i", ()).unwrap();
+        assert_eq!(names(&nodes, None), ["a", "i", "g"]);
+    }
+
+    #[test]
+    fn delete_removes_the_subtree() {
+        let mut nodes = sample();
+        let mut removed: Vec<i32> = nodes.delete(2).unwrap().iter().map(|n| n.id).collect();
+        removed.sort();
+        assert_eq!(removed, [2, 4, 5]);
+        assert_eq!(names(&nodes, Some(1)), ["c"]);
+        assert!(!nodes.contains(4));
+        assert_eq!(nodes.delete(2), Err(EditError::NotFound(2)));
+    }
+
+    #[test]
+    fn duplicate_copies_the_subtree_under_a_new_name() {
+        let mut nodes = sample();
+        let copy = nodes.duplicate(2).unwrap();
+        assert_eq!(names(&nodes, Some(1)), ["b", "b 2", "c"]);
+        assert_eq!(names(&nodes, Some(copy)), ["d", "e"]);
+        assert_eq!(names(&nodes, Some(2)), ["d", "e"]);
+        assert_eq!(nodes.validate_name(copy, "b 2"), Ok(()));
+        assert_eq!(nodes.len(), 10);
+    }
+
+    #[test]
+    fn unique_name_counts_up_past_taken_names() {
+        let mut nodes = sample();
+        assert_eq!(nodes.unique_name(Some(1), "d"), "d");
+        assert_eq!(nodes.unique_name(Some(1), "b"), "b 2");
+        nodes.add_child(Some(1), "b 2", ()).unwrap();
+        assert_eq!(nodes.unique_name(Some(1), "b"), "b 3");
+    }
+
+    #[test]
+    fn check_drop_rejects_cycles() {
+        let nodes = sample();
+        assert_eq!(
+            nodes.check_drop(1, 4, DropPosition::Inside),
+            Err(EditError::WouldCycle {
+                id: 1,
+                parent_id: 4
+            })
+        );
+        assert_eq!(
+            nodes.check_drop(2, 4, DropPosition::Before),
+            Err(EditError::WouldCycle {
+                id: 2,
+                parent_id: 2
+            })
+        );
+        assert_eq!(
+            nodes.check_drop(2, 2, DropPosition::After),
+            Err(EditError::OntoItself(2))
+        );
+        assert_eq!(
+            nodes.check_drop(2, 9, DropPosition::Inside),
+            Err(EditError::NotFound(9))
+        );
+        assert_eq!(nodes.check_drop(4, 6, DropPosition::Inside), Ok(()));
+        assert_eq!(nodes.check_drop(1, 7, DropPosition::After), Ok(()));
+    }
+
+    #[test]
+    fn rank_between_halves_the_gap() {
+        assert_eq!(rank_between(None, None), Some(0));
+        assert_eq!(rank_between(Some(0), None), Some(RANK_STEP));
+        assert_eq!(rank_between(None, Some(0)), Some(-RANK_STEP));
+        assert_eq!(rank_between(Some(0), Some(4)), Some(2));
+        assert_eq!(rank_between(Some(0), Some(1)), None);
+        assert_eq!(rank_between(Some(i64::MAX), None), None);
+    }
+
+    #[test]
+    fn moves_renumber_siblings_once_the_gap_is_used_up() {
+        let mut nodes = roots(40);
+        // Each move halves the gap before node 2, which runs out after 16 moves.
+        for id in 3..=40 {
+            nodes.move_before(id, 2).unwrap();
+        }
+        let mut expected = vec![1];
+        expected.extend(3..=40);
+        expected.push(2);
+        assert_eq!(nodes.child_ids(None), expected);
+        let ranks: Vec<i64> = nodes.children(None).map(|n| n.rank).collect();
+        assert!(ranks.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn drop_nodes_keeps_the_given_order() {
+        let mut nodes = sample();
+        nodes.drop_nodes(&[4, 3], 7, DropPosition::Inside).unwrap();
+        assert_eq!(names(&nodes, Some(7)), ["d", "c"]);
+
+        let mut nodes = sample();
+        nodes.drop_nodes(&[4, 3], 7, DropPosition::After).unwrap();
+        assert_eq!(names(&nodes, None), ["a", "g", "d", "c"]);
+
+        let mut nodes = sample();
+        nodes.drop_nodes(&[4, 3], 1, DropPosition::Before).unwrap();
+        assert_eq!(names(&nodes, None), ["d", "c", "a", "g"]);
+    }
+
+    #[test]
+    fn drop_nodes_moves_nothing_if_one_node_is_refused() {
+        let mut nodes = sample();
+        assert_eq!(
+            nodes.drop_nodes(&[3, 1], 4, DropPosition::Inside),
+            Err(EditError::WouldCycle {
+                id: 1,
+                parent_id: 4
+            })
+        );
+        assert_eq!(names(&nodes, Some(1)), ["b", "c"]);
+        assert!(!nodes.has_children(4));
+    }
+
+    #[test]
+    fn ids_run_out_instead_of_overflowing() {
+        let mut nodes: NodeList = NodeList::new(vec![Node::new(i32::MAX, None, "a")]);
+        assert_eq!(nodes.add_child(None, "b", ()), Err(EditError::OutOfIds));
+        assert_eq!(nodes.duplicate(i32::MAX), Err(EditError::OutOfIds));
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(
+            i32::fresh([i32::MAX - 2].into_iter(), 2),
+            Some(vec![i32::MAX - 1, i32::MAX])
+        );
+        assert_eq!(i32::fresh([i32::MAX - 2].into_iter(), 3), None);
+    }
+
+    #[test]
+    fn update_in_place_keeps_the_sibling_order() {
+        let mut nodes = roots(3);
+        nodes.rename(1, "one").unwrap();
+        assert_eq!(names(&nodes, None), ["one", "2", "3"]);
+        assert_eq!(
+            nodes.update(1, |n| n.id = 7),
+            Err(EditError::IdChanged { id: 1, new_id: 7 })
+        );
+    }
+
+    #[test]
+    fn merge_children_changes_nothing_on_a_cycle() {
+        let mut nodes: NodeList = NodeList::new(vec![
+            Node::new(1, None, "a").with_lazy_children(),
+            Node::new(2, Some(1), "b"),
+        ]);
+        // 2 would be under 3, which is under 2.
+        let loaded = vec![Node::new(3, Some(2), "c"), Node::new(2, Some(3), "b")];
+        assert_eq!(
+            nodes.merge_children(1, loaded),
+            Err(EditError::WouldCycle {
+                id: 3,
+                parent_id: 2
+            })
+        );
+        assert!(nodes.get(1).unwrap().lazy);
+        assert!(!nodes.contains(3));
+
+        let loaded = vec![Node::new(3, Some(1), "c"), Node::new(4, Some(3), "d")];
+        nodes.merge_children(1, loaded).unwrap();
+        assert!(!nodes.get(1).unwrap().lazy);
+        assert_eq!(names(&nodes, Some(1)), ["b", "c"]);
+        assert_eq!(names(&nodes, Some(3)), ["d"]);
+    }
+
+    #[test]
+    fn validate_reports_every_problem() {
+        assert_eq!(sample().validate(), Ok(()));
+        let nodes: NodeList = NodeList::new(vec![
+            Node::new(1, None, "a"),
+            Node::new(1, None, "a again"),
+            Node::new(2, Some(9), "orphan"),
+            Node::new(3, Some(3), "own parent"),
+            Node::new(4, Some(5), "x"),
+            Node::new(5, Some(4), "y"),
+        ]);
+        assert_eq!(
+            nodes.validate(),
+            Err(vec![
+                TreeError::DuplicateId(1),
+                TreeError::Orphan {
+                    id: 2,
+                    parent_id: 9
+                },
+                TreeError::SelfParent(3),
+                TreeError::Cycle(vec![4, 5]),
+            ])
+        );
+    }
+
+    #[test]
+    fn renumbering_keeps_the_moved_node_in_rank_order() {
+        let mut nodes = roots(5);
+        nodes.move_node(2, None).unwrap();
+        nodes.move_node(1, None).unwrap();
+        // Halve the gap between the two rows after 4 until they are adjacent.
+        for _ in 0..64 {
+            let ids = nodes.child_ids(None);
+            let ranks: Vec<i64> = ids.iter().map(|&id| nodes.get(id).unwrap().rank).collect();
+            if ranks[3] - ranks[2] <= 1 {
+                break;
+            }
+            nodes.move_after(ids[3], 4).unwrap();
+        }
+        assert_eq!(nodes.child_ids(None), [3, 4, 5, 2, 1]);
+
+        nodes.move_after(3, 5).unwrap();
+        assert_eq!(nodes.child_ids(None), [4, 5, 3, 2, 1]);
+        let ranks: Vec<i64> = nodes.children(None).map(|n| n.rank).collect();
+        assert!(ranks.windows(2).all(|w| w[0] < w[1]), "{:?}", ranks);
+    }
+
+    #[test]
+    fn try_new_refuses_duplicate_ids() {
+        let nodes = vec![Node::<(), i32>::new(1, None, "a"), Node::new(1, None, "b")];
+        assert_eq!(
+            NodeList::try_new(nodes).unwrap_err(),
+            [TreeError::DuplicateId(1)]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Conversion between the flat `parent_id` lists the tree works on and nested
+//! trees where each node holds its children, the shape of nested JSON.
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Node, NodeId, NodeList, TreeError};
+
+/// A node with its subtree inline.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode<T = (), I = i32> {
+    pub id: I,
+    pub name: String,
+    /// See [`Node::rank`]. Usually left out, in which case `children` keeps its order.
+    #[serde(default)]
+    pub rank: i64,
+    #[serde(default)]
+    pub lazy: bool,
+    #[serde(default)]
+    pub data: T,
+    #[serde(default)]
+    pub children: Vec<TreeNode<T, I>>,
+}
+
+impl<T, I: NodeId> NodeList<T, I> {
+    /// Flattens nested trees, one per root, into a list. Fails if an id is used twice.
+    pub fn from_tree(roots: Vec<TreeNode<T, I>>) -> Result<NodeList<T, I>, Vec<TreeError<I>>> {
+        let mut list = Vec::new();
+        let mut stack: Vec<(Option<I>, TreeNode<T, I>)> =
+            roots.into_iter().rev().map(|root| (None, root)).collect();
+        // Depth first, so siblings of equal rank are added, and ordered, as given.
+        while let Some((parent_id, tree)) = stack.pop() {
+            let id = tree.id;
+            stack.extend(tree.children.into_iter().rev().map(|c| (Some(id), c)));
+            list.push(Node {
+                id,
+                parent_id,
+                name: tree.name,
+                rank: tree.rank,
+                lazy: tree.lazy,
+                data: tree.data,
+            });
+        }
+        NodeList::try_new(list)
+    }
+}
+
+impl<T: Clone, I: NodeId> NodeList<T, I> {
+    /// The nodes as nested trees, one per root, with children in rank order.
+    /// Fails if the list isn't a valid tree, as orphans and cycles have no place
+    /// in one and would be lost.
+    pub fn to_tree(&self) -> Result<Vec<TreeNode<T, I>>, Vec<TreeError<I>>> {
+        self.validate()?;
+        Ok(self
+            .children(None)
+            .map(|root| self.tree_node(root))
+            .collect())
+    }
+
+    fn tree_node(&self, node: &Node<T, I>) -> TreeNode<T, I> {
+        TreeNode {
+            id: node.id,
+            name: node.name.clone(),
+            rank: node.rank,
+            lazy: node.lazy,
+            data: node.data.clone(),
+            children: self
+                .children(Some(node.id))
+                .map(|child| self.tree_node(child))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{names, sample};
+
+    fn tree(id: i32, name: &str, children: Vec<TreeNode>) -> TreeNode {
+        TreeNode {
+            id,
+            name: name.to_owned(),
+            rank: 0,
+            lazy: false,
+            data: (),
+            children,
+        }
+    }
+
+    #[test]
+    fn from_tree_keeps_the_order_given() {
+        let nodes = NodeList::from_tree(vec![
+            tree(1, "a", vec![tree(2, "c", vec![]), tree(3, "b", vec![])]),
+            tree(4, "d", vec![]),
+        ])
+        .unwrap();
+        assert_eq!(names(&nodes, None), ["a", "d"]);
+        assert_eq!(names(&nodes, Some(1)), ["c", "b"]);
+        assert_eq!(nodes.get(3).unwrap().parent_id, Some(1));
+    }
+
+    #[test]
+    fn to_tree_round_trips() {
+        let nested = sample().to_tree().unwrap();
+        let ids: Vec<i32> = nested.iter().map(|root| root.id).collect();
+        assert_eq!(ids, [1, 7]);
+        let ids: Vec<i32> = nested[0].children.iter().map(|c| c.id).collect();
+        assert_eq!(ids, [2, 3]);
+        assert_eq!(nested[0].children[1].children[0].name, "f");
+        let again = NodeList::from_tree(nested.clone())
+            .unwrap()
+            .to_tree()
+            .unwrap();
+        assert_eq!(again, nested);
+    }
+
+    #[test]
+    fn from_tree_rejects_duplicate_ids() {
+        let result = NodeList::from_tree(vec![tree(1, "a", vec![]), tree(1, "b", vec![])]);
+        assert_eq!(result.unwrap_err(), [TreeError::DuplicateId(1)]);
+    }
+
+    #[test]
+    fn to_tree_rejects_orphans() {
+        let nodes: NodeList = NodeList::new(vec![Node::new(1, Some(9), "a")]);
+        assert_eq!(
+            nodes.to_tree(),
+            Err(vec![TreeError::Orphan {
+                id: 1,
+                parent_id: 9
+            }])
+        );
+    }
+}
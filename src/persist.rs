@@ -0,0 +1,190 @@
+use std::fmt;
+use std::rc::Rc;
+
+use gloo::console::log;
+use rexie::{ObjectStore, Rexie, TransactionMode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sycamore::futures::spawn_local_scoped;
+use sycamore::prelude::*;
+
+use crate::components::NodeState;
+use crate::model::{Node, NodeId};
+
+const NODE_STORE: &str = "nodes";
+/// Holds [`SEEDED`] once the tree has been seeded, so a tree whose nodes were all
+/// deleted isn't seeded again.
+const META_STORE: &str = "meta";
+const SEEDED: &str = "seeded";
+
+#[derive(Debug)]
+pub enum PersistError {
+    Db(rexie::Error),
+    Serde(serde_wasm_bindgen::Error),
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistError::Db(err) => write!(f, "indexed db error: {}", err),
+            PersistError::Serde(err) => write!(f, "could not (de)serialize node: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {}
+
+impl From<rexie::Error> for PersistError {
+    fn from(err: rexie::Error) -> Self {
+        PersistError::Db(err)
+    }
+}
+
+impl From<serde_wasm_bindgen::Error> for PersistError {
+    fn from(err: serde_wasm_bindgen::Error) -> Self {
+        PersistError::Serde(err)
+    }
+}
+
+/// An IndexedDB database holding one record per node, keyed by `id`, and a
+/// record of whether it has been seeded.
+pub struct TreeStore {
+    db: Rexie,
+}
+
+impl TreeStore {
+    pub async fn open(name: &str) -> Result<TreeStore, PersistError> {
+        let db = Rexie::builder(name)
+            .version(2)
+            .add_object_store(ObjectStore::new(NODE_STORE).key_path("id"))
+            .add_object_store(ObjectStore::new(META_STORE))
+            .build()
+            .await?;
+        Ok(TreeStore { db })
+    }
+
+    pub async fn load<T: DeserializeOwned, I: NodeId + DeserializeOwned>(
+        &self,
+    ) -> Result<Vec<Node<T, I>>, PersistError> {
+        let tx = self
+            .db
+            .transaction(&[NODE_STORE], TransactionMode::ReadOnly)?;
+        let store = tx.store(NODE_STORE)?;
+        let records = store.get_all(None, None, None, None).await?;
+        tx.done().await?;
+        records
+            .into_iter()
+            .map(|(_, value)| Ok(serde_wasm_bindgen::from_value(value)?))
+            .collect()
+    }
+
+    /// Writes `changed` nodes and deletes `removed` ids in a single transaction.
+    pub async fn save<T: Serialize, I: NodeId + Serialize>(
+        &self,
+        changed: &[Node<T, I>],
+        removed: &[I],
+    ) -> Result<(), PersistError> {
+        let tx = self
+            .db
+            .transaction(&[NODE_STORE], TransactionMode::ReadWrite)?;
+        let store = tx.store(NODE_STORE)?;
+        for node in changed {
+            store
+                .put(&serde_wasm_bindgen::to_value(node)?, None)
+                .await?;
+        }
+        for id in removed {
+            store.delete(&serde_wasm_bindgen::to_value(id)?).await?;
+        }
+        tx.done().await?;
+        Ok(())
+    }
+
+    /// Loads the stored tree, or writes and returns `initial` if the store has never
+    /// been seeded. A tree emptied by deleting every node stays empty.
+    pub async fn load_or_seed<T, I>(
+        &self,
+        initial: Vec<Node<T, I>>,
+    ) -> Result<Vec<Node<T, I>>, PersistError>
+    where
+        T: Serialize + DeserializeOwned,
+        I: NodeId + Serialize + DeserializeOwned,
+    {
+        let nodes = self.load().await?;
+        if self.is_seeded().await? {
+            return Ok(nodes);
+        }
+        // A store written before the marker existed was seeded if it holds nodes.
+        if !nodes.is_empty() {
+            self.seed::<T, I>(&[]).await?;
+            return Ok(nodes);
+        }
+        self.seed(&initial).await?;
+        Ok(initial)
+    }
+
+    async fn is_seeded(&self) -> Result<bool, PersistError> {
+        let tx = self
+            .db
+            .transaction(&[META_STORE], TransactionMode::ReadOnly)?;
+        let store = tx.store(META_STORE)?;
+        let seeded = store.get(&serde_wasm_bindgen::to_value(SEEDED)?).await?;
+        tx.done().await?;
+        Ok(!seeded.is_undefined())
+    }
+
+    /// Writes `nodes` and the seeded marker in a single transaction.
+    async fn seed<T: Serialize, I: NodeId + Serialize>(
+        &self,
+        nodes: &[Node<T, I>],
+    ) -> Result<(), PersistError> {
+        let tx = self
+            .db
+            .transaction(&[NODE_STORE, META_STORE], TransactionMode::ReadWrite)?;
+        let store = tx.store(NODE_STORE)?;
+        for node in nodes {
+            store
+                .put(&serde_wasm_bindgen::to_value(node)?, None)
+                .await?;
+        }
+        tx.store(META_STORE)?
+            .put(
+                &serde_wasm_bindgen::to_value(&true)?,
+                Some(&serde_wasm_bindgen::to_value(SEEDED)?),
+            )
+            .await?;
+        tx.done().await?;
+        Ok(())
+    }
+}
+
+/// Keeps `store` in step with `state.nodes`: every change made through `state`
+/// writes just the nodes it added, changed or removed, as listed in
+/// [`NodeState::changed`]. Changes made directly on `state.nodes` aren't saved.
+pub fn sync_to_store<'a, T, I>(cx: Scope<'a>, store: Rc<TreeStore>, state: &'a NodeState<T, I>)
+where
+    T: Serialize + Clone + 'static,
+    I: NodeId + Serialize,
+{
+    create_effect(cx, move || {
+        let ids = state.changed.get();
+        if ids.is_empty() {
+            return;
+        }
+        let nodes = state.nodes.get_untracked();
+        let (mut changed, mut removed) = (Vec::new(), Vec::new());
+        for &id in ids.iter() {
+            match nodes.get(id) {
+                Some(node) => changed.push(node.clone()),
+                None => removed.push(id),
+            }
+        }
+
+        let store = store.clone();
+        spawn_local_scoped(cx, async move {
+            if let Err(err) = store.save(&changed, &removed).await {
+                log!(format!("Failed to persist tree: {}", err));
+            }
+        });
+    });
+}
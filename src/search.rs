@@ -0,0 +1,255 @@
+//! Finding nodes by name, for the search box that filters a tree.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::model::{NodeId, NodeList};
+
+/// How the search text is matched against node names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchMode {
+    /// The name contains the text.
+    #[default]
+    Substring,
+    /// The name contains the characters of the text in order, not necessarily
+    /// next to each other: "sls" matches "Sales".
+    Fuzzy,
+    /// The text is a regular expression found somewhere in the name.
+    Regex,
+}
+
+/// What to search for. An empty `text` matches nothing and filters nothing.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchQuery {
+    pub text: String,
+    pub mode: MatchMode,
+    pub case_sensitive: bool,
+}
+
+impl SearchQuery {
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Compiles the query for matching. Fails only on an invalid regex.
+    pub fn matcher(&self) -> Result<Matcher, regex::Error> {
+        let pattern = match self.mode {
+            MatchMode::Substring => regex::escape(&self.text),
+            MatchMode::Regex => self.text.clone(),
+            MatchMode::Fuzzy => {
+                return Ok(Matcher::Fuzzy {
+                    chars: self.text.chars().collect(),
+                    case_sensitive: self.case_sensitive,
+                })
+            }
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()?;
+        Ok(Matcher::Pattern(regex))
+    }
+}
+
+/// A compiled [`SearchQuery`].
+#[derive(Debug, Clone)]
+pub enum Matcher {
+    Pattern(Regex),
+    Fuzzy {
+        chars: Vec<char>,
+        case_sensitive: bool,
+    },
+}
+
+impl Matcher {
+    /// The byte ranges of `name` to highlight, or `None` if it doesn't match.
+    /// A match can have no ranges, e.g. for the regex `^`.
+    pub fn find(&self, name: &str) -> Option<Vec<Range<usize>>> {
+        match self {
+            Matcher::Pattern(regex) => {
+                let ranges: Vec<Range<usize>> = regex
+                    .find_iter(name)
+                    .map(|m| m.range())
+                    .filter(|range| !range.is_empty())
+                    .collect();
+                (!ranges.is_empty() || regex.is_match(name)).then_some(ranges)
+            }
+            Matcher::Fuzzy {
+                chars,
+                case_sensitive,
+            } => {
+                let same = |a: char, b: char| {
+                    if *case_sensitive {
+                        a == b
+                    } else {
+                        a.to_lowercase().eq(b.to_lowercase())
+                    }
+                };
+                let mut wanted = chars.iter().copied().peekable();
+                let mut ranges: Vec<Range<usize>> = Vec::new();
+                for (start, c) in name.char_indices() {
+                    let Some(&w) = wanted.peek() else {
+                        break;
+                    };
+                    if !same(c, w) {
+                        continue;
+                    }
+                    wanted.next();
+                    let end = start + c.len_utf8();
+                    match ranges.last_mut() {
+                        // Consecutive characters are highlighted as one run.
+                        Some(last) if last.end == start => last.end = end,
+                        _ => ranges.push(start..end),
+                    }
+                }
+                wanted.peek().is_none().then_some(ranges)
+            }
+        }
+    }
+}
+
+/// The outcome of [`NodeList::search`].
+#[derive(Debug, Clone)]
+pub struct SearchResults<I = i32> {
+    /// The matching nodes, with the byte ranges of their names to highlight.
+    pub matches: HashMap<I, Vec<Range<usize>>>,
+    /// Nodes above a match. They are expanded so the matches can be seen.
+    pub ancestors: HashSet<I>,
+    /// Matches and their ancestors: the nodes left in a filtered tree.
+    pub shown: HashSet<I>,
+}
+
+impl<T, I: NodeId> NodeList<T, I> {
+    /// Matches every node's name. O(n) plus O(d) per match.
+    pub fn search(&self, matcher: &Matcher) -> SearchResults<I> {
+        let mut results = SearchResults {
+            matches: HashMap::new(),
+            ancestors: HashSet::new(),
+            shown: HashSet::new(),
+        };
+        for node in self.iter() {
+            let Some(ranges) = matcher.find(&node.name) else {
+                continue;
+            };
+            results.matches.insert(node.id, ranges);
+            results.shown.insert(node.id);
+            for ancestor in self.ancestors(node.id) {
+                // Everything further up was added with an earlier match.
+                if !results.ancestors.insert(ancestor.id) {
+                    break;
+                }
+                results.shown.insert(ancestor.id);
+            }
+        }
+        results
+    }
+}
+
+/// Splits `text` into runs, each flagged with whether it is in one of `ranges`
+/// (as returned by [`Matcher::find`]).
+pub fn highlight<'a>(text: &'a str, ranges: &[Range<usize>]) -> Vec<(&'a str, bool)> {
+    let mut parts = Vec::new();
+    let mut at = 0;
+    for range in ranges {
+        if range.start > at {
+            parts.push((&text[at..range.start], false));
+        }
+        parts.push((&text[range.clone()], true));
+        at = range.end;
+    }
+    if at < text.len() {
+        parts.push((&text[at..], false));
+    }
+    parts
+}
+
+#[cfg(test)]
+// Single ranges here are one highlighted run, not a range meant as a list.
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+    use crate::model::Node;
+
+    fn query(text: &str, mode: MatchMode, case_sensitive: bool) -> Matcher {
+        SearchQuery {
+            text: text.to_owned(),
+            mode,
+            case_sensitive,
+        }
+        .matcher()
+        .unwrap()
+    }
+
+    #[test]
+    fn substring_matches_every_occurrence() {
+        let matcher = query("a", MatchMode::Substring, false);
+        assert_eq!(matcher.find("Banana"), Some(vec![1..2, 3..4, 5..6]));
+        assert_eq!(query("A", MatchMode::Substring, true).find("Banana"), None);
+        // The text is matched literally, not as a regex.
+        assert_eq!(
+            query("a.", MatchMode::Substring, false).find("Banana"),
+            None
+        );
+    }
+
+    #[test]
+    fn fuzzy_matches_characters_in_order() {
+        let matcher = query("sls", MatchMode::Fuzzy, false);
+        assert_eq!(matcher.find("Sales"), Some(vec![0..1, 2..3, 4..5]));
+        assert_eq!(matcher.find("Lass"), None);
+        assert_eq!(
+            query("al", MatchMode::Fuzzy, false).find("Sales"),
+            Some(vec![1..3])
+        );
+    }
+
+    #[test]
+    fn regex_matches_and_reports_bad_patterns() {
+        assert_eq!(
+            query("^s.l", MatchMode::Regex, false).find("Sales"),
+            Some(vec![0..3])
+        );
+        assert_eq!(
+            query("^", MatchMode::Regex, false).find("Sales"),
+            Some(vec![])
+        );
+        let bad = SearchQuery {
+            text: "(".to_owned(),
+            mode: MatchMode::Regex,
+            case_sensitive: false,
+        };
+        assert!(bad.matcher().is_err());
+    }
+
+    #[test]
+    fn search_collects_matches_and_their_ancestors() {
+        let nodes: NodeList = NodeList::new(vec![
+            Node::new(1, None, "Company"),
+            Node::new(2, Some(1), "Sales"),
+            Node::new(3, Some(2), "Sales EU"),
+            Node::new(4, None, "Other"),
+        ]);
+        let results = nodes.search(&query("sales", MatchMode::Substring, false));
+        assert_eq!(results.matches.len(), 2);
+        assert_eq!(results.matches[&3], [0..5]);
+        assert_eq!(results.ancestors, HashSet::from([1, 2]));
+        assert_eq!(results.shown, HashSet::from([1, 2, 3]));
+    }
+
+    #[test]
+    fn highlight_splits_the_text() {
+        assert_eq!(
+            highlight("Banana", &[1..2, 3..4]),
+            [
+                ("B", false),
+                ("a", true),
+                ("n", false),
+                ("a", true),
+                ("na", false)
+            ]
+        );
+        assert_eq!(highlight("ab", &[0..2]), [("ab", true)]);
+        assert_eq!(highlight("ab", &[]), [("ab", false)]);
+    }
+}
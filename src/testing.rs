@@ -0,0 +1,38 @@
+//! Fixtures shared by the unit tests.
+
+use crate::model::{Node, NodeList};
+
+/// ```text
+/// 1 a
+/// ├ 2 b
+/// │ ├ 4 d
+/// │ └ 5 e
+/// └ 3 c
+///   └ 6 f
+/// 7 g
+/// ```
+pub(crate) fn sample() -> NodeList {
+    NodeList::new(vec![
+        Node::new(1, None, "a"),
+        Node::new(2, Some(1), "b"),
+        Node::new(3, Some(1), "c"),
+        Node::new(4, Some(2), "d"),
+        Node::new(5, Some(2), "e"),
+        Node::new(6, Some(3), "f"),
+        Node::new(7, None, "g"),
+    ])
+}
+
+/// `count` roots, each named after its id.
+pub(crate) fn roots(count: i32) -> NodeList {
+    NodeList::new(
+        (1..=count)
+            .map(|id| Node::new(id, None, &id.to_string()))
+            .collect(),
+    )
+}
+
+/// The names of the children of `parent_id`, in order.
+pub(crate) fn names(nodes: &NodeList, parent_id: Option<i32>) -> Vec<&str> {
+    nodes.children(parent_id).map(|n| n.name.as_str()).collect()
+}
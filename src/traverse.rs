@@ -0,0 +1,189 @@
+//! Walking a [`NodeList`]: up to the root, down through a subtree and across siblings.
+//!
+//! Every walk is bounded by the number of nodes, so a list with a cycle (see
+//! [`NodeList::validate`]) can't hang it.
+
+use std::collections::VecDeque;
+
+use crate::model::{Node, NodeId, NodeList};
+
+/// The parent of a node, then its parent, up to the root. See [`NodeList::ancestors`].
+pub struct Ancestors<'a, T, I> {
+    nodes: &'a NodeList<T, I>,
+    next: Option<I>,
+    remaining: usize,
+}
+
+impl<'a, T, I: NodeId> Iterator for Ancestors<'a, T, I> {
+    type Item = &'a Node<T, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        let node = self.nodes.get(self.next?)?;
+        self.next = node.parent_id;
+        Some(node)
+    }
+}
+
+/// A node's descendants, depth first. See [`NodeList::descendants`].
+pub struct Descendants<'a, T, I> {
+    nodes: &'a NodeList<T, I>,
+    stack: Vec<&'a Node<T, I>>,
+    remaining: usize,
+}
+
+impl<'a, T, I: NodeId> Iterator for Descendants<'a, T, I> {
+    type Item = &'a Node<T, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        let node = self.stack.pop()?;
+        self.stack.extend(self.nodes.children(Some(node.id)).rev());
+        Some(node)
+    }
+}
+
+/// A node's descendants, level by level. See [`NodeList::descendants_breadth_first`].
+pub struct BreadthFirst<'a, T, I> {
+    nodes: &'a NodeList<T, I>,
+    queue: VecDeque<&'a Node<T, I>>,
+    remaining: usize,
+}
+
+impl<'a, T, I: NodeId> Iterator for BreadthFirst<'a, T, I> {
+    type Item = &'a Node<T, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        let node = self.queue.pop_front()?;
+        self.queue.extend(self.nodes.children(Some(node.id)));
+        Some(node)
+    }
+}
+
+impl<T, I: NodeId> NodeList<T, I> {
+    /// The parent of `id`, its parent and so on up to the root. Empty for roots
+    /// and for ids not in the list.
+    pub fn ancestors(&self, id: I) -> Ancestors<'_, T, I> {
+        Ancestors {
+            nodes: self,
+            next: self.get(id).and_then(|n| n.parent_id),
+            remaining: self.len(),
+        }
+    }
+
+    /// The nodes from the root down to `id`, e.g. for a breadcrumb. Empty if
+    /// `id` isn't in the list.
+    pub fn path(&self, id: I) -> Vec<&Node<T, I>> {
+        let mut path: Vec<&Node<T, I>> = self.get(id).into_iter().collect();
+        path.extend(self.ancestors(id));
+        path.reverse();
+        path
+    }
+
+    /// How many ancestors `id` has: 0 for roots.
+    pub fn depth(&self, id: I) -> Option<usize> {
+        self.get(id).map(|_| self.ancestors(id).count())
+    }
+
+    /// The descendants of `id`, depth first and in rank order: each child is
+    /// followed by its own descendants before the next child.
+    pub fn descendants(&self, id: I) -> Descendants<'_, T, I> {
+        Descendants {
+            nodes: self,
+            stack: self.children(Some(id)).rev().collect(),
+            remaining: self.len(),
+        }
+    }
+
+    /// The descendants of `id`, breadth first: all children, then all grandchildren
+    /// and so on, each level in rank order.
+    pub fn descendants_breadth_first(&self, id: I) -> BreadthFirst<'_, T, I> {
+        BreadthFirst {
+            nodes: self,
+            queue: self.children(Some(id)).collect(),
+            remaining: self.len(),
+        }
+    }
+
+    /// The other children of the parent of `id`, in rank order.
+    pub fn siblings(&self, id: I) -> impl DoubleEndedIterator<Item = &Node<T, I>> {
+        let parent_id = self.get(id).map(|n| n.parent_id);
+        parent_id
+            .into_iter()
+            .flat_map(move |parent_id| self.children(parent_id))
+            .filter(move |n| n.id != id)
+    }
+
+    /// The deepest node that is `a` or one of its ancestors, and `b` or one of
+    /// its ancestors. `None` if the two are in different trees or not in the list.
+    pub fn lowest_common_ancestor(&self, a: I, b: I) -> Option<I> {
+        let (mut a, mut b) = (self.path(a), self.path(b));
+        if a.len() > b.len() {
+            std::mem::swap(&mut a, &mut b);
+        }
+        a.iter()
+            .zip(&b)
+            .take_while(|(x, y)| x.id == y.id)
+            .last()
+            .map(|(x, _)| x.id)
+    }
+
+    /// The number of nodes in the subtree of `id`, counting `id` itself; 0 if
+    /// `id` isn't in the list.
+    pub fn subtree_size(&self, id: I) -> usize {
+        if self.contains(id) {
+            1 + self.descendants(id).count()
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{Node, NodeList};
+    use crate::testing::sample;
+
+    fn ids<'a>(nodes: impl Iterator<Item = &'a Node>) -> Vec<i32> {
+        nodes.map(|n| n.id).collect()
+    }
+
+    #[test]
+    fn walks_up() {
+        let nodes = sample();
+        assert_eq!(ids(nodes.ancestors(5)), [2, 1]);
+        assert_eq!(ids(nodes.path(5).into_iter()), [1, 2, 5]);
+        assert_eq!(nodes.depth(5), Some(2));
+        assert_eq!(nodes.depth(7), Some(0));
+        assert_eq!(nodes.depth(9), None);
+    }
+
+    #[test]
+    fn walks_down() {
+        let nodes = sample();
+        assert_eq!(ids(nodes.descendants(1)), [2, 4, 5, 3, 6]);
+        assert_eq!(ids(nodes.descendants_breadth_first(1)), [2, 3, 4, 5, 6]);
+        assert_eq!(nodes.subtree_size(1), 6);
+        assert_eq!(nodes.subtree_size(9), 0);
+    }
+
+    #[test]
+    fn walks_across() {
+        let nodes = sample();
+        assert_eq!(ids(nodes.siblings(4)), [5]);
+        assert_eq!(ids(nodes.siblings(7)), [1]);
+        assert_eq!(nodes.lowest_common_ancestor(4, 6), Some(1));
+        assert_eq!(nodes.lowest_common_ancestor(4, 5), Some(2));
+        assert_eq!(nodes.lowest_common_ancestor(2, 4), Some(2));
+        assert_eq!(nodes.lowest_common_ancestor(4, 7), None);
+    }
+
+    #[test]
+    fn stops_on_a_cycle() {
+        let nodes: NodeList =
+            NodeList::new(vec![Node::new(1, Some(2), "a"), Node::new(2, Some(1), "b")]);
+        assert_eq!(nodes.ancestors(1).count(), 2);
+        assert_eq!(nodes.descendants(1).count(), 2);
+    }
+}